@@ -1,5 +1,33 @@
 extern crate gcc;
 
+use std::fs::{self, File};
+use std::io::Read;
+
+// 64 bit FNV-1a of the names and contents of the emulator's C sources and headers, the
+// cache keys its measurements on it
+fn native_digest() -> String {
+    let mut paths: Vec<_> = fs::read_dir("native").unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()), Some("c") | Some("h")))
+        .collect();
+
+    paths.sort();
+
+    let mut digest = 0xcbf29ce484222325u64;
+
+    for path in &paths {
+        let mut bytes = path.file_name().unwrap().to_string_lossy().into_owned().into_bytes();
+        bytes.push(0);
+        File::open(path).unwrap().read_to_end(&mut bytes).unwrap();
+
+        for &byte in &bytes {
+            digest = (digest ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{:016x}", digest)
+}
+
 fn main() {
     gcc::compile_library("libmusahi.a",
                          &["native/m68kcpu.c",
@@ -9,4 +37,6 @@ fn main() {
                            "native/m68kopnz.c",
                            "native/m68kops.c",
                            "native/m68k_wrapper.c"]);
+
+    println!("cargo:rustc-env=NATIVE_DIGEST={}", native_digest());
 }
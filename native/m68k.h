@@ -291,7 +291,51 @@ int m68k_execute(int num_cycles);
 /* execute num_cycles worth of instructions.  returns number of cycles used */
 int m68k_execute(int num_cycles);
 
-int m68k_execute_inst_count(int instruction_count, int* cycles);
+/* Register state loaded before each instruction run by m68k_execute_inst_count() */
+typedef struct
+{
+	unsigned int d[8];
+	unsigned int a[8];
+	unsigned int sr;
+} m68k_preset;
+
+/* Memory access that aborted an instruction, filled in by m68k_raise_fault() */
+typedef struct
+{
+	unsigned int faulted;
+	unsigned int address;
+	unsigned int size;
+	unsigned int write;
+	unsigned int pc;
+} m68k_fault;
+
+/* Bus cycles of an instruction. Counted by the memory callbacks through m68k_bus */
+typedef struct
+{
+	unsigned int reads;
+	unsigned int writes;
+} m68k_bus_count;
+
+extern m68k_bus_count m68k_bus;
+
+/* One bus cycle of the access trace. fc is the function code driven on FC0-FC2, split is
+ * set on both word cycles of a long moved over the 16 bit bus */
+typedef struct
+{
+	unsigned int address;
+	unsigned int size;
+	unsigned int write;
+	unsigned int fc;
+	unsigned int split;
+} m68k_access;
+
+/* execute instruction_count instructions, each one starting at its own offset with its own
+ * register preset. The cycles used by every instruction are stored in cycles and the bus
+ * cycles in bus. Instructions that fault get 0 cycles and their entry in faults filled in */
+int m68k_execute_inst_count(int instruction_count, const unsigned int* offsets, const m68k_preset* presets, int* cycles, m68k_bus_count* bus, m68k_fault* faults);
+
+/* Aborts the instruction being run by m68k_execute_inst_count(). Only valid from memory callbacks */
+void m68k_raise_fault(unsigned int address, unsigned int size, unsigned int write);
 
 /* These functions let you read/write/modify the number of cycles left to run
  * while m68k_execute() is running.
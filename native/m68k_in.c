@@ -4433,6 +4433,8 @@ M68KMAKE_OP(divs, 16, ., d)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -4471,6 +4473,8 @@ M68KMAKE_OP(divs, 16, ., .)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -4507,6 +4511,8 @@ M68KMAKE_OP(divu, 16, ., d)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -4533,6 +4539,8 @@ M68KMAKE_OP(divu, 16, ., .)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -7263,7 +7271,10 @@ M68KMAKE_OP(moveq, 32, ., .)
 M68KMAKE_OP(muls, 16, ., d)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(DY) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = DY;
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -7277,7 +7288,10 @@ M68KMAKE_OP(muls, 16, ., d)
 M68KMAKE_OP(muls, 16, ., .)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(M68KMAKE_GET_OPER_AY_16) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = M68KMAKE_GET_OPER_AY_16;
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -7291,7 +7305,10 @@ M68KMAKE_OP(muls, 16, ., .)
 M68KMAKE_OP(mulu, 16, ., d)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_16(DY) * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = MASK_OUT_ABOVE_16(DY);
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -7305,7 +7322,10 @@ M68KMAKE_OP(mulu, 16, ., d)
 M68KMAKE_OP(mulu, 16, ., .)
 {
 	uint* r_dst = &DX;
-	uint res = M68KMAKE_GET_OPER_AY_16 * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = M68KMAKE_GET_OPER_AY_16;
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -1,6 +1,7 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <stdarg.h>
+#include <string.h>
 #include <time.h>
 #include "m68k.h"
 #include "m68kcpu.h"
@@ -30,8 +31,26 @@ void cpu_write_byte(unsigned int address, unsigned int value);
 void cpu_write_word(unsigned int address, unsigned int value);
 void cpu_write_long(unsigned int address, unsigned int value);
 
-#define MAX_ROM 0xfff
-unsigned char* g_rom;
+/* Memory map of the flat RAM handed over by m68k_wrapper_set_memory():
+ *
+ * 0x00000 - 0x003ff  vector table. Every exception vector points at the handler
+ * 0x00400 - 0x00fff  landing area filled with NOPs, the first one is the exception handler.
+ *                    Address registers and absolute addresses used by the tables point
+ *                    here so jumps land on valid code
+ * 0x01000 - end      the instructions being measured, the rest is zeroed RAM
+ *
+ * The supervisor stack starts at the top of the landing area. The layout is rebuilt
+ * for every m68k_run_instructions() call so writes made by one table can't leak into
+ * the next one.
+ */
+#define VECTOR_COUNT 256
+#define HANDLER 0x400
+#define STACK_TOP 0x1000
+#define CODE_BASE 0x1000
+
+unsigned char* g_ram;
+unsigned int g_ram_size;
+unsigned int g_cpu_type = M68K_CPU_TYPE_68000;
 
 void exit_error(char* fmt, ...)
 {
@@ -50,100 +69,209 @@ void exit_error(char* fmt, ...)
 	va_end(args);
 	fprintf(stderr, "\n");
 	pc = m68k_get_reg(NULL, M68K_REG_PPC);
-	m68k_disassemble(buff, pc, M68K_CPU_TYPE_68000);
+	m68k_disassemble(buff, pc, g_cpu_type);
 	fprintf(stderr, "At %04x: %s\n", pc, buff);
 
 	exit(EXIT_FAILURE);
 }
 
+/* Access trace of the instructions being run, see m68k_run_instructions() */
+static m68k_access* g_trace;
+static unsigned int g_trace_capacity;
+static unsigned int g_trace_len;
+
+/* Function code of the access in progress, set by the CPU before every access */
+static unsigned int g_fc;
+
+static void set_fc(unsigned int fc)
+{
+	g_fc = fc;
+}
+
+/* Counts one bus cycle and logs it to the trace if there is room left */
+static void trace_access(unsigned int address, unsigned int size, int write, int split)
+{
+	if (g_trace_len < g_trace_capacity) {
+		m68k_access* access = &g_trace[g_trace_len++];
+
+		access->address = address;
+		access->size = size;
+		access->write = write;
+		access->fc = g_fc;
+		access->split = split;
+	}
+
+	if (write)
+		m68k_bus.writes++;
+	else
+		m68k_bus.reads++;
+}
+
+/* Counts the bus cycles of an access. The 68000 and 68010 have a 16 bit data bus so
+ * longs take two cycles, the 68020 moves them in one. Musashi hands over a long in one
+ * call, so which half a real CPU moves first isn't known here (a 68000 writes the low
+ * word first for move.l to -(An)). Both halves are logged in ascending address order
+ * and marked as split so the order isn't taken for a measurement */
+static void count_access(unsigned int address, unsigned int size, int write)
+{
+	if (size == 4 && g_cpu_type < M68K_CPU_TYPE_68EC020) {
+		trace_access(address, 2, write, 1);
+		trace_access(address + 2, 2, write, 1);
+	} else {
+		trace_access(address, size, write, 0);
+	}
+}
+
+/* True if the size bytes at address are outside of the RAM */
+static int out_of_range(unsigned int address, unsigned int size)
+{
+	return address >= g_ram_size || g_ram_size - address < size;
+}
+
 
-/* Read data from RAM, ROM, or a device */
+/* Accesses outside of the RAM abort the instruction and are reported back as a fault */
 unsigned int m68k_read_memory_8(unsigned int address)
 {
-	//printf("m68k_read_memory_8: %08x\n", address);
-	if (address > MAX_ROM)
-		exit_error("Attempted to read byte from ROM address %08x", address);
+	if (out_of_range(address, 1))
+		m68k_raise_fault(address, 1, 0);
 
-	return READ_BYTE(g_rom, address);
+	count_access(address, 1, 0);
+	return READ_BYTE(g_ram, address);
 }
 
 unsigned int m68k_read_memory_16(unsigned int address)
 {
-	//printf("m68k_read_memory_16: %08x\n", address);
-	if (address > MAX_ROM) {
-		printf("A0 %08x\n", REG_A[0]);
-		exit_error("Attempted to read word from ROM address %08x", address);
-	}
+	if (out_of_range(address, 2))
+		m68k_raise_fault(address, 2, 0);
 
-	return READ_WORD(g_rom, address);
+	count_access(address, 2, 0);
+	return READ_WORD(g_ram, address);
 }
 
 unsigned int m68k_read_memory_32(unsigned int address)
 {
-	//printf("m68k_read_memory_32: %08x\n", address);
-	if(address > MAX_ROM)
-		exit_error("Attempted to read long from ROM address %08x", address);
+	if (out_of_range(address, 4))
+		m68k_raise_fault(address, 4, 0);
 
-	return READ_LONG(g_rom, address);
+	count_access(address, 4, 0);
+	return READ_LONG(g_ram, address);
 }
 
 
 unsigned int m68k_read_disassembler_16(unsigned int address)
 {
-	//printf("%08x\n", address);
-	if(address > MAX_ROM)
-		exit_error("Disassembler attempted to read word from ROM address %08x", address);
+	if (out_of_range(address, 2))
+		exit_error("Disassembler attempted to read word from address %08x outside of RAM", address);
 
-	return READ_WORD(g_rom, address);
+	return READ_WORD(g_ram, address);
 }
 
 unsigned int m68k_read_disassembler_32(unsigned int address)
 {
-	//printf("%08x\n", address);
-	if(address > MAX_ROM)
-		exit_error("Dasm attempted to read long from ROM address %08x", address);
+	if (out_of_range(address, 4))
+		exit_error("Dasm attempted to read long from address %08x outside of RAM", address);
 
-	return READ_LONG(g_rom, address);
+	return READ_LONG(g_ram, address);
 }
 
 
-/* Write data to RAM or a device */
+/* Write data to RAM */
 void m68k_write_memory_8(unsigned int address, unsigned int value)
 {
-	(void)address;
-	(void)value;
+	if (out_of_range(address, 1))
+		m68k_raise_fault(address, 1, 1);
+
+	count_access(address, 1, 1);
+	WRITE_BYTE(g_ram, address, value);
 }
 
 void m68k_write_memory_16(unsigned int address, unsigned int value)
 {
-	(void)address;
-	(void)value;
+	if (out_of_range(address, 2))
+		m68k_raise_fault(address, 2, 1);
+
+	count_access(address, 2, 1);
+	WRITE_WORD(g_ram, address, value);
 }
 
 void m68k_write_memory_32(unsigned int address, unsigned int value)
 {
-	(void)address;
-	(void)value;
+	if (out_of_range(address, 4))
+		m68k_raise_fault(address, 4, 1);
+
+	count_access(address, 4, 1);
+	WRITE_LONG(g_ram, address, value);
 }
 
 
-void m68k_wrapper_init() {
+void m68k_wrapper_init(unsigned int cpu_type) {
+	g_cpu_type = cpu_type;
 	m68k_init();
-	m68k_set_cpu_type(M68K_CPU_TYPE_68000);
-	m68k_pulse_reset();
+	m68k_set_cpu_type(cpu_type);
+	m68k_set_fc_callback(set_fc);
+}
+
+/* The RAM is owned by the caller and has to stay valid until the next call */
+void m68k_wrapper_set_memory(unsigned char* memory, unsigned int size)
+{
+	g_ram = memory;
+	g_ram_size = size;
+}
+
+static void setup_memory(void* data, unsigned int size)
+{
+	memset(g_ram, 0, g_ram_size);
+
+	/* Reset vectors (initial SSP and PC) followed by every exception vector */
+	WRITE_LONG(g_ram, 0, STACK_TOP);
+	WRITE_LONG(g_ram, 4, CODE_BASE);
+
+	for (int i = 2; i < VECTOR_COUNT; ++i) {
+		WRITE_LONG(g_ram, i * 4, HANDLER);
+	}
+
+	for (int i = HANDLER; i < CODE_BASE; i += 2) {
+		WRITE_WORD(g_ram, i, 0x4e71);
+	}
+
+	memcpy(g_ram + CODE_BASE, data, size);
 }
 
-void m68k_run_instructions(void* data, int inst_count, int* cycle_result)
+void m68k_run_instructions(void* data, int size, int inst_count, const unsigned int* offsets, const m68k_preset* presets, int* cycle_result, m68k_bus_count* bus, m68k_fault* faults, m68k_access* trace, unsigned int trace_capacity)
 {
-	g_rom = data;
+	unsigned int* pcs;
+
+	if (out_of_range(CODE_BASE, size))
+		exit_error("%d bytes of instructions don't fit in %d bytes of RAM", size, g_ram_size);
+
+	setup_memory(data, size);
+
+	pcs = malloc(inst_count * sizeof(unsigned int));
+
+	for (int i = 0; i < inst_count; ++i) {
+		pcs[i] = CODE_BASE + offsets[i];
+	}
+
 	m68k_pulse_reset();
-	m68k_execute_inst_count(inst_count, cycle_result);
+
+	/* Every bus cycle is logged, so the entries of an instruction are the next
+	 * reads + writes ones of the trace. Logging stops once the trace is full */
+	g_trace = trace;
+	g_trace_capacity = trace_capacity;
+	g_trace_len = 0;
+
+	m68k_execute_inst_count(inst_count, pcs, presets, cycle_result, bus, faults);
+
+	g_trace = NULL;
+	g_trace_capacity = 0;
+
+	free(pcs);
 	/*
-	int pc = 0;
+	int pc = CODE_BASE;
 
 	for (int i = 0; i < inst_count; ++i) {
 		char buff[100];
-		pc += m68k_disassemble(buff, pc, M68K_CPU_TYPE_68000);
+		pc += m68k_disassemble(buff, pc, g_cpu_type);
 		printf("inst %04d - %s - %d\n", i, buff, pc);
 	}
 	*/
@@ -116,7 +116,7 @@
  * want to properly emulate the m68010 or higher. (moves uses function codes
  * to read/write data from different address spaces)
  */
-#define M68K_EMULATE_FC             OPT_OFF
+#define M68K_EMULATE_FC             OPT_ON
 #define M68K_SET_FC_CALLBACK(A)     your_set_fc_handler_function(A)
 
 
@@ -38,6 +38,7 @@
 /* ================================ INCLUDES ============================== */
 /* ======================================================================== */
 
+#include <setjmp.h>
 #include "m68kops.h"
 #include "m68kcpu.h"
 
@@ -695,7 +696,135 @@ int m68k_execute(int num_cycles)
 	return num_cycles;
 }
 
-int m68k_execute_inst_count(int instruction_count, int* cycles)
+/* The fixed counts of the 68000 cycle table for the register forms */
+#define MULU_TABLE_CYCLES 54
+#define MULS_TABLE_CYCLES 54
+#define DIVU_TABLE_CYCLES 140
+#define DIVS_TABLE_CYCLES 158
+
+static uint count_ones(uint value)
+{
+	uint count = 0;
+
+	for(; value; value &= value - 1)
+		count++;
+
+	return count;
+}
+
+/* 38+2n, n is the number of ones in the source */
+sint m68ki_mulu_cycles(uint src)
+{
+	if(!CPU_TYPE_IS_000(CPU_TYPE))
+		return 0;
+
+	return 38 + 2 * count_ones(src & 0xffff) - MULU_TABLE_CYCLES;
+}
+
+/* 38+2n, n is the number of 01 and 10 pairs in the source with a zero appended */
+sint m68ki_muls_cycles(uint src)
+{
+	uint bits = (src & 0xffff) << 1;
+
+	if(!CPU_TYPE_IS_000(CPU_TYPE))
+		return 0;
+
+	return 38 + 2 * count_ones((bits ^ (bits >> 1)) & 0xffff) - MULS_TABLE_CYCLES;
+}
+
+/* Follows the 68000's shift and subtract loop, after Jorge Cwik's analysis of the
+ * 68000 division timing. The divisor is never zero here */
+sint m68ki_divu_cycles(uint dividend, uint divisor)
+{
+	uint hdivisor = (divisor & 0xffff) << 16;
+	uint mcycles = 38;
+	int i;
+
+	if(!CPU_TYPE_IS_000(CPU_TYPE))
+		return 0;
+
+	if((dividend >> 16) >= (divisor & 0xffff))
+		return 10 - DIVU_TABLE_CYCLES;
+
+	for(i = 0; i < 15; i++)
+	{
+		uint carry = dividend & 0x80000000;
+
+		dividend <<= 1;
+
+		if(carry)
+		{
+			dividend -= hdivisor;
+		}
+		else
+		{
+			mcycles += 2;
+
+			if(dividend >= hdivisor)
+			{
+				dividend -= hdivisor;
+				mcycles--;
+			}
+		}
+	}
+
+	return mcycles * 2 - DIVU_TABLE_CYCLES;
+}
+
+sint m68ki_divs_cycles(sint dividend, sint divisor)
+{
+	uint adividend = dividend < 0 ? -(uint)dividend : (uint)dividend;
+	uint adivisor = divisor < 0 ? -divisor : divisor;
+	uint mcycles = dividend < 0 ? 7 : 6;
+	uint aquot;
+	int i;
+
+	if(!CPU_TYPE_IS_000(CPU_TYPE))
+		return 0;
+
+	if((adividend >> 16) >= adivisor)
+		return (mcycles + 2) * 2 - DIVS_TABLE_CYCLES;
+
+	aquot = adividend / adivisor;
+	mcycles += 55;
+
+	if(divisor >= 0)
+	{
+		if(dividend >= 0)
+			mcycles--;
+		else
+			mcycles++;
+	}
+
+	/* One more for every zero in the top 15 bits of the quotient */
+	for(i = 0; i < 15; i++)
+	{
+		if(!(aquot & 0x8000))
+			mcycles++;
+
+		aquot <<= 1;
+	}
+
+	return mcycles * 2 - DIVS_TABLE_CYCLES;
+}
+
+m68k_bus_count m68k_bus;
+
+static jmp_buf m68ki_fault_trap;
+static m68k_fault* m68ki_current_fault;
+
+void m68k_raise_fault(unsigned int address, unsigned int size, unsigned int write)
+{
+	m68ki_current_fault->faulted = 1;
+	m68ki_current_fault->address = address;
+	m68ki_current_fault->size = size;
+	m68ki_current_fault->write = write;
+	m68ki_current_fault->pc = REG_PPC;
+
+	longjmp(m68ki_fault_trap, 1);
+}
+
+int m68k_execute_inst_count(int instruction_count, const unsigned int* offsets, const m68k_preset* presets, int* cycles, m68k_bus_count* bus, m68k_fault* faults)
 {
 	int i = 0;
 
@@ -710,13 +839,33 @@ int m68k_execute_inst_count(int instruction_count, int* cycles)
 	{
 		SET_CYCLES(0);
 
-		for (int i = 0; i < 8; ++i) {
-			REG_A[i] = 8;
-			REG_D[i] = 1;
+		faults[i].faulted = 0;
+		m68ki_current_fault = &faults[i];
+
+		m68k_bus.reads = 0;
+		m68k_bus.writes = 0;
+
+		if (setjmp(m68ki_fault_trap) != 0) {
+			cycles[i] = 0;
+			bus[i] = m68k_bus;
+			continue;
 		}
 
+		/* SR first as changing the S bit swaps A7 */
+		m68ki_set_sr(presets[i].sr);
+
+		for (int r = 0; r < 8; ++r) {
+			REG_A[r] = presets[i].a[r];
+			REG_D[r] = presets[i].d[r];
+		}
+
+		/* Branches leave PC somewhere else so every instruction is started at its own offset */
+		REG_PC = offsets[i];
 		REG_PPC = REG_PC;
 
+		/* Set the address space for reads, PC relative operands switch it to program space */
+		m68ki_use_data_space(); /* auto-disable (see m68kcpu.h) */
+
 		/* Read an instruction and call its handler */
 		REG_IR = m68ki_read_imm_16();
 		m68ki_instruction_jump_table[REG_IR]();
@@ -726,6 +875,7 @@ int m68k_execute_inst_count(int instruction_count, int* cycles)
 		//printf("cycle count %d\n", count);
 
 		cycles[i] = count;
+		bus[i] = m68k_bus;
 	}
 
 	return 0;
@@ -874,6 +874,13 @@ extern uint           m68ki_aerr_address;
 extern uint           m68ki_aerr_write_mode;
 extern uint           m68ki_aerr_fc;
 
+/* Data dependent MULU/MULS/DIVU/DIVS timing of the 68000. Each returns the cycles to add
+ * to the fixed count of the cycle table, 0 on the other CPUs */
+sint m68ki_mulu_cycles(uint src);
+sint m68ki_muls_cycles(uint src);
+sint m68ki_divu_cycles(uint dividend, uint divisor);
+sint m68ki_divs_cycles(sint dividend, sint divisor);
+
 /* Read data immediately after the program counter */
 INLINE uint m68ki_read_imm_16(void);
 INLINE uint m68ki_read_imm_32(void);
@@ -390,6 +390,8 @@ void m68k_op_divs_16_d(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -428,6 +430,8 @@ void m68k_op_divs_16_ai(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -466,6 +470,8 @@ void m68k_op_divs_16_pi(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -504,6 +510,8 @@ void m68k_op_divs_16_pd(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -542,6 +550,8 @@ void m68k_op_divs_16_di(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -580,6 +590,8 @@ void m68k_op_divs_16_ix(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -618,6 +630,8 @@ void m68k_op_divs_16_aw(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -656,6 +670,8 @@ void m68k_op_divs_16_al(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -694,6 +710,8 @@ void m68k_op_divs_16_pcdi(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -732,6 +750,8 @@ void m68k_op_divs_16_pcix(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -770,6 +790,8 @@ void m68k_op_divs_16_i(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divs_cycles(MAKE_INT_32(*r_dst), src));
+
 		if((uint32)*r_dst == 0x80000000 && src == -1)
 		{
 			FLAG_Z = 0;
@@ -806,6 +828,8 @@ void m68k_op_divu_16_d(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -832,6 +856,8 @@ void m68k_op_divu_16_ai(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -858,6 +884,8 @@ void m68k_op_divu_16_pi(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -884,6 +912,8 @@ void m68k_op_divu_16_pd(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -910,6 +940,8 @@ void m68k_op_divu_16_di(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -936,6 +968,8 @@ void m68k_op_divu_16_ix(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -962,6 +996,8 @@ void m68k_op_divu_16_aw(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -988,6 +1024,8 @@ void m68k_op_divu_16_al(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -1014,6 +1052,8 @@ void m68k_op_divu_16_pcdi(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -1040,6 +1080,8 @@ void m68k_op_divu_16_pcix(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -1066,6 +1108,8 @@ void m68k_op_divu_16_i(void)
 
 	if(src != 0)
 	{
+		USE_CYCLES(m68ki_divu_cycles(*r_dst, src));
+
 		uint quotient = *r_dst / src;
 		uint remainder = *r_dst % src;
 
@@ -11547,7 +11591,10 @@ void m68k_op_moveq_32(void)
 void m68k_op_muls_16_d(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(DY) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = DY;
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11561,7 +11608,10 @@ void m68k_op_muls_16_d(void)
 void m68k_op_muls_16_ai(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AY_AI_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AY_AI_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11575,7 +11625,10 @@ void m68k_op_muls_16_ai(void)
 void m68k_op_muls_16_pi(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AY_PI_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AY_PI_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11589,7 +11642,10 @@ void m68k_op_muls_16_pi(void)
 void m68k_op_muls_16_pd(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AY_PD_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AY_PD_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11603,7 +11659,10 @@ void m68k_op_muls_16_pd(void)
 void m68k_op_muls_16_di(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AY_DI_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AY_DI_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11617,7 +11676,10 @@ void m68k_op_muls_16_di(void)
 void m68k_op_muls_16_ix(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AY_IX_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AY_IX_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11631,7 +11693,10 @@ void m68k_op_muls_16_ix(void)
 void m68k_op_muls_16_aw(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AW_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AW_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11645,7 +11710,10 @@ void m68k_op_muls_16_aw(void)
 void m68k_op_muls_16_al(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_AL_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_AL_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11659,7 +11727,10 @@ void m68k_op_muls_16_al(void)
 void m68k_op_muls_16_pcdi(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_PCDI_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_PCDI_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11673,7 +11744,10 @@ void m68k_op_muls_16_pcdi(void)
 void m68k_op_muls_16_pcix(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_PCIX_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_PCIX_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11687,7 +11761,10 @@ void m68k_op_muls_16_pcix(void)
 void m68k_op_muls_16_i(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(OPER_I_16()) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+	uint src = OPER_I_16();
+	uint res = MASK_OUT_ABOVE_32(MAKE_INT_16(src) * MAKE_INT_16(MASK_OUT_ABOVE_16(*r_dst)));
+
+	USE_CYCLES(m68ki_muls_cycles(src));
 
 	*r_dst = res;
 
@@ -11701,7 +11778,10 @@ void m68k_op_muls_16_i(void)
 void m68k_op_mulu_16_d(void)
 {
 	uint* r_dst = &DX;
-	uint res = MASK_OUT_ABOVE_16(DY) * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = MASK_OUT_ABOVE_16(DY);
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11715,7 +11795,10 @@ void m68k_op_mulu_16_d(void)
 void m68k_op_mulu_16_ai(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AY_AI_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AY_AI_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11729,7 +11812,10 @@ void m68k_op_mulu_16_ai(void)
 void m68k_op_mulu_16_pi(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AY_PI_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AY_PI_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11743,7 +11829,10 @@ void m68k_op_mulu_16_pi(void)
 void m68k_op_mulu_16_pd(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AY_PD_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AY_PD_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11757,7 +11846,10 @@ void m68k_op_mulu_16_pd(void)
 void m68k_op_mulu_16_di(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AY_DI_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AY_DI_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11771,7 +11863,10 @@ void m68k_op_mulu_16_di(void)
 void m68k_op_mulu_16_ix(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AY_IX_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AY_IX_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11785,7 +11880,10 @@ void m68k_op_mulu_16_ix(void)
 void m68k_op_mulu_16_aw(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AW_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AW_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11799,7 +11897,10 @@ void m68k_op_mulu_16_aw(void)
 void m68k_op_mulu_16_al(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_AL_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_AL_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11813,7 +11914,10 @@ void m68k_op_mulu_16_al(void)
 void m68k_op_mulu_16_pcdi(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_PCDI_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_PCDI_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11827,7 +11931,10 @@ void m68k_op_mulu_16_pcdi(void)
 void m68k_op_mulu_16_pcix(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_PCIX_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_PCIX_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -11841,7 +11948,10 @@ void m68k_op_mulu_16_pcix(void)
 void m68k_op_mulu_16_i(void)
 {
 	uint* r_dst = &DX;
-	uint res = OPER_I_16() * MASK_OUT_ABOVE_16(*r_dst);
+	uint src = OPER_I_16();
+	uint res = src * MASK_OUT_ABOVE_16(*r_dst);
+
+	USE_CYCLES(m68ki_mulu_cycles(src));
 
 	*r_dst = res;
 
@@ -0,0 +1,802 @@
+// Assembles all statements of a table with the selected assembler backend.
+//
+// External assemblers (vasm, GNU as) get a single batch file per table. Every statement
+// sits between a begin_N and an end_N label and the source ends with a word holding
+// end_N-begin_N per statement, so the flat binary carries the length of every statement
+// after the code. Statements the tool rejects are found by the line numbers of its errors
+// and left out of the next run. The messages of every rejected statement are kept and
+// sorted into categories for the diagnostics report.
+//
+// The backend is selected once at startup and handed to the tables in the Context. A
+// cross-check assembles every table with the built-in encoder as well (vasm if that is the
+// selected one, run with the given path and flags) and reports the statements they don't
+// agree on byte for byte.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::process::{self, Command};
+
+use cache::{self, Cache};
+use cpu::Cpu;
+use diagnostics::Diagnostics;
+use dialect::{self, Syntax};
+use encoder::{self, EncodeError};
+
+#[cfg(target_os="windows")]
+const VASM_EXE: &str = "bin/win/vasmm68k_mot.exe";
+
+#[cfg(target_os="macos")]
+const VASM_EXE: &str = "bin/mac/vasmm68k_mot";
+
+#[cfg(any(target_os="linux",
+          target_os="freebsd",
+          target_os="dragonfly",
+          target_os="netbsd",
+          target_os="openbsd"))]
+const VASM_EXE: &str = "vasmm68k_mot";
+
+const GAS_EXE: &str = "m68k-elf-as";
+const OBJCOPY_EXE: &str = "m68k-elf-objcopy";
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Backend {
+    Vasm,
+    Gas(Syntax),
+    Builtin,
+    Fake,
+}
+
+impl Backend {
+    pub fn from_name(name: &str) -> Option<Backend> {
+        match name {
+            "vasm" => Some(Backend::Vasm),
+            "gas" => Some(Backend::Gas(Syntax::Motorola)),
+            "gas-mit" => Some(Backend::Gas(Syntax::Mit)),
+            "builtin" => Some(Backend::Builtin),
+            "fake" => Some(Backend::Fake),
+            _ => None,
+        }
+    }
+}
+
+// Why a statement wasn't assembled
+#[derive(Copy, Clone, PartialEq)]
+pub enum Category {
+    // Addressing modes the instruction doesn't take
+    IllegalEa = 0,
+    // Operation size the instruction doesn't have
+    UnsupportedSize = 1,
+    // Statement the assembler can't parse, e.g. a typo in an Op, or an instruction the
+    // selected CPU doesn't have
+    Syntax = 2,
+    // The assembler didn't run or produced no usable output
+    ToolFailure = 3,
+}
+
+impl Category {
+    pub fn all() -> &'static [Category] {
+        &[Category::IllegalEa, Category::UnsupportedSize, Category::Syntax, Category::ToolFailure]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::IllegalEa => "illegal EA",
+            Category::UnsupportedSize => "unsupported size",
+            Category::Syntax => "syntax error",
+            Category::ToolFailure => "tool failure",
+        }
+    }
+
+    // Name in the json output
+    pub fn key(self) -> &'static str {
+        match self {
+            Category::IllegalEa => "illegal_ea",
+            Category::UnsupportedSize => "unsupported_size",
+            Category::Syntax => "syntax_error",
+            Category::ToolFailure => "tool_failure",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Rejection {
+    pub category: Category,
+    // Error output of the assembler for the statement
+    pub message: String,
+}
+
+impl Rejection {
+    fn new(category: Category, message: &str) -> Rejection {
+        Rejection { category, message: message.to_owned() }
+    }
+}
+
+// Code of a statement or why it was rejected
+pub type Assembled = Result<Vec<u8>, Rejection>;
+
+// Backend and tool settings from the command line
+pub struct Config {
+    pub backend: Backend,
+    // Executable of the assembler, the backend's default if None
+    pub path: Option<String>,
+    // Passed to the assembler after the CPU flag
+    pub flags: Vec<String>,
+}
+
+pub trait Assembler {
+    fn name(&self) -> &'static str;
+
+    // The statement in the assembler's own syntax
+    fn translate(&self, statement: &str) -> String;
+
+    // Version of the assembler, part of the keys of the cache
+    fn version(&self) -> String;
+
+    // Code of every statement or why it was rejected
+    fn assemble(&self, cpu: Cpu, statements: &[&str]) -> Vec<Assembled>;
+}
+
+// An external assembler that turns a batch source file into a flat binary
+trait Tool {
+    fn name(&self) -> &'static str;
+
+    fn translate(&self, statement: &str) -> String;
+
+    fn version(&self) -> String;
+
+    // Directive for the length words at the end of the source
+    fn word_directive(&self) -> &'static str;
+
+    // Assembles source into binary, the tool's error output if that fails. Err if the
+    // tool can't be run at all
+    fn run(&self, cpu: Cpu, source: &str, binary: &str) -> io::Result<Result<(), String>>;
+
+    // Source line number and text of an error message, None for other output
+    fn error(&self, line: &str) -> Option<(usize, String)>;
+}
+
+impl<T: Tool> Assembler for T {
+    fn name(&self) -> &'static str {
+        Tool::name(self)
+    }
+
+    fn translate(&self, statement: &str) -> String {
+        Tool::translate(self, statement)
+    }
+
+    fn version(&self) -> String {
+        Tool::version(self)
+    }
+
+    fn assemble(&self, cpu: Cpu, statements: &[&str]) -> Vec<Assembled> {
+        assemble_batches(self, cpu, statements)
+    }
+}
+
+struct Vasm {
+    path: String,
+    flags: Vec<String>,
+}
+
+impl Tool for Vasm {
+    fn name(&self) -> &'static str {
+        "vasm"
+    }
+
+    // The statements are written in vasm's syntax
+    fn translate(&self, statement: &str) -> String {
+        statement.to_owned()
+    }
+
+    fn version(&self) -> String {
+        tool_version(&self.path, "-v")
+    }
+
+    fn word_directive(&self) -> &'static str {
+        "dc.w"
+    }
+
+    fn run(&self, cpu: Cpu, source: &str, binary: &str) -> io::Result<Result<(), String>> {
+        let output = Command::new(&self.path)
+            .arg("-no-opt")
+            .arg("-maxerrors=0")
+            .arg(cpu.assembler_flag())
+            .args(&self.flags)
+            .arg(source)
+            .arg("-Fbin")
+            .arg("-o")
+            .arg(binary)
+            .output()?;
+
+        Ok(match output.status.success() {
+            true => Ok(()),
+            false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+        })
+    }
+
+    // error 2 in line 5 of "target/batch_1_0.s": unknown mnemonic <foo>
+    fn error(&self, line: &str) -> Option<(usize, String)> {
+        if !line.starts_with("error") && !line.starts_with("fatal error") {
+            return None;
+        }
+
+        let number = line.find(" in line ")
+            .map(|start| &line[start + 9..])
+            .and_then(|rest| rest.split(' ').next())
+            .and_then(|number| number.parse::<usize>().ok())?;
+
+        let text = line.find("\": ").map_or(line, |start| &line[start + 3..]);
+        Some((number, text.to_owned()))
+    }
+}
+
+// First line the tool prints for arg, e.g. vasm 1.9 (c) in 2002-2022 Volker Barthelmann
+fn tool_version(path: &str, arg: &str) -> String {
+    match Command::new(path).arg(arg).output() {
+        Ok(output) => {
+            let text = String::from_utf8_lossy(&output.stdout).into_owned() + &String::from_utf8_lossy(&output.stderr);
+            text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("unknown").to_owned()
+        }
+        Err(_) => "unknown".to_owned(),
+    }
+}
+
+// GNU as for m68k-elf, the object file is turned into a flat binary by objcopy
+struct Gas {
+    syntax: Syntax,
+    path: String,
+    objcopy: String,
+    flags: Vec<String>,
+}
+
+impl Gas {
+    fn new(syntax: Syntax, path: Option<String>, flags: Vec<String>) -> Gas {
+        // objcopy of the same toolchain, e.g. m68k-linux-gnu-as and m68k-linux-gnu-objcopy
+        let objcopy = match path {
+            Some(ref path) if path.ends_with("as") => format!("{}objcopy", &path[..path.len() - 2]),
+            _ => OBJCOPY_EXE.to_owned(),
+        };
+
+        Gas {
+            syntax,
+            path: path.unwrap_or(GAS_EXE.to_owned()),
+            objcopy,
+            flags,
+        }
+    }
+}
+
+impl Tool for Gas {
+    fn name(&self) -> &'static str {
+        match self.syntax {
+            Syntax::Motorola => "GNU as",
+            Syntax::Mit => "GNU as (MIT syntax)",
+        }
+    }
+
+    fn translate(&self, statement: &str) -> String {
+        dialect::translate(statement, self.syntax)
+    }
+
+    fn version(&self) -> String {
+        tool_version(&self.path, "--version")
+    }
+
+    fn word_directive(&self) -> &'static str {
+        ".word"
+    }
+
+    fn run(&self, cpu: Cpu, source: &str, binary: &str) -> io::Result<Result<(), String>> {
+        let object = binary.replace(".bin", ".o");
+
+        let output = Command::new(&self.path)
+            .arg(cpu.assembler_flag())
+            .args(&self.flags)
+            .arg("-o")
+            .arg(&object)
+            .arg(source)
+            .output()?;
+
+        if !output.status.success() {
+            let _ = fs::remove_file(&object);
+            return Ok(Err(String::from_utf8_lossy(&output.stderr).into_owned()));
+        }
+
+        let output = Command::new(&self.objcopy)
+            .arg("-O")
+            .arg("binary")
+            .arg("-j")
+            .arg(".text")
+            .arg(&object)
+            .arg(binary)
+            .output();
+
+        let _ = fs::remove_file(&object);
+        let output = output?;
+
+        Ok(match output.status.success() {
+            true => Ok(()),
+            false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+        })
+    }
+
+    // target/batch_1_0.s:5: Error: operands mismatch -- statement `abcd.b %d0,%a0' ignored
+    fn error(&self, line: &str) -> Option<(usize, String)> {
+        let end = line.find(": Error:").or(line.find(": Fatal error:"))?;
+        let number = line[..end].rsplit(':').next().and_then(|number| number.parse::<usize>().ok())?;
+
+        Some((number, line[end + 2..].to_owned()))
+    }
+}
+
+struct Builtin;
+
+impl Assembler for Builtin {
+    fn name(&self) -> &'static str {
+        "built-in encoder"
+    }
+
+    // The encoder reads the vasm syntax the statements are written in
+    fn translate(&self, statement: &str) -> String {
+        statement.to_owned()
+    }
+
+    // The encoder changes without a new package version, so its source is part of it
+    fn version(&self) -> String {
+        format!("{} encoder {}", env!("CARGO_PKG_VERSION"), cache::digest(include_str!("encoder.rs").as_bytes()))
+    }
+
+    fn assemble(&self, _cpu: Cpu, statements: &[&str]) -> Vec<Assembled> {
+        statements.iter().map(|statement| {
+            encoder::encode(statement).map_err(|err| {
+                let category = match err {
+                    EncodeError::IllegalOperands => Category::IllegalEa,
+                    EncodeError::IllegalSize => Category::UnsupportedSize,
+                    EncodeError::Syntax(_) | EncodeError::Unsupported => Category::Syntax,
+                };
+
+                Rejection::new(category, &err.describe())
+            })
+        }).collect()
+    }
+}
+
+// Assembles every statement to a NOP, so the generator runs without any assembler
+struct Fake;
+
+impl Assembler for Fake {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn translate(&self, statement: &str) -> String {
+        statement.to_owned()
+    }
+
+    fn version(&self) -> String {
+        "1".to_owned()
+    }
+
+    fn assemble(&self, _cpu: Cpu, statements: &[&str]) -> Vec<Assembled> {
+        vec![Ok(vec![0x4e, 0x71]); statements.len()]
+    }
+}
+
+// The assembler every table goes through and the one the cross-check compares with
+pub struct Selection {
+    assembler: Box<dyn Assembler>,
+    reference: Option<Box<dyn Assembler>>,
+    flags: Vec<String>,
+    // Backend, version and flags of the assembler for the cache, see identity()
+    identity: Option<String>,
+    // Statements assembled by both assemblers and the ones they disagree on
+    compared: usize,
+    mismatches: usize,
+}
+
+// Source lines per statement: begin label, statement, end label
+const LINES_PER_STATEMENT: usize = 3;
+
+fn source<T: Tool>(tool: &T, statements: &[&str], pending: &[usize]) -> String {
+    let mut source = String::new();
+
+    for (n, &i) in pending.iter().enumerate() {
+        source.push_str(&format!("begin_{}:\n {}\nend_{}:\n", n, tool.translate(statements[i]), n));
+    }
+
+    for n in 0..pending.len() {
+        source.push_str(&format!(" {} end_{}-begin_{}\n", tool.word_directive(), n, n));
+    }
+
+    source
+}
+
+// Category of an error message of vasm or GNU as for statement
+fn categorize(message: &str, statement: &str) -> Category {
+    let message = message.to_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|word| message.contains(word));
+    let sized = statement.split_whitespace().next().unwrap_or("").contains('.');
+
+    // vasm and GNU as name the instructions missing on the CPU (e.g. extb.l with -m68000)
+    // like this, GNU as says "invalid operand mode for this architecture" for addressing modes
+    if mentions(&["instruction not supported on selected architecture", "invalid instruction for this architecture"]) {
+        Category::Syntax
+    } else if mentions(&["size", "extension", "suffix"]) {
+        Category::UnsupportedSize
+    } else if mentions(&["operand", "addressing mode", "mismatch"]) {
+        Category::IllegalEa
+    } else if mentions(&["unknown operator"]) && sized {
+        // GNU as looks the mnemonic up with its size, abcd.w is an unknown operator
+        Category::UnsupportedSize
+    } else {
+        Category::Syntax
+    }
+}
+
+// Positions in pending of the statements the tool reported errors for, with their messages
+fn rejected_statements<T: Tool>(tool: &T, stderr: &str, pending: &[usize]) -> Vec<(usize, Vec<String>)> {
+    let mut rejected: Vec<(usize, Vec<String>)> = Vec::new();
+
+    for (number, text) in stderr.lines().filter_map(|line| tool.error(line)).filter(|&(number, _)| number > 0) {
+        let n = (number - 1) / LINES_PER_STATEMENT;
+
+        if n >= pending.len() {
+            continue;
+        }
+
+        match rejected.iter().position(|&(m, _)| m == n) {
+            Some(i) => rejected[i].1.push(text),
+            None => rejected.push((n, vec![text])),
+        }
+    }
+
+    rejected
+}
+
+enum BatchError {
+    // Positions in pending of the rejected statements and their messages
+    Rejected(Vec<(usize, Vec<String>)>),
+    // Nothing to attribute the failure to
+    Failed(String),
+}
+
+// Splits the flat binary into the code of every statement using the lengths at its end
+fn split_code(binary: &[u8], count: usize) -> Option<Vec<Vec<u8>>> {
+    if binary.len() < count * 2 {
+        return None;
+    }
+
+    let (code, lengths) = binary.split_at(binary.len() - count * 2);
+    let mut offset = 0;
+    let mut parts = Vec::with_capacity(count);
+
+    for length in lengths.chunks(2) {
+        let length = ((length[0] as usize) << 8) | length[1] as usize;
+
+        if offset + length > code.len() {
+            return None;
+        }
+
+        parts.push(code[offset..offset + length].to_vec());
+        offset += length;
+    }
+
+    if offset == code.len() { Some(parts) } else { None }
+}
+
+// The code of every pending statement, or the rejected ones. The batch files are removed
+// after the run, batch only tells the runs of a table apart
+fn run<T: Tool>(tool: &T, cpu: Cpu, statements: &[&str], pending: &[usize], batch: usize)
+    -> Result<Vec<Vec<u8>>, BatchError>
+{
+    let file_in = format!("target/batch_{}_{}.s", process::id(), batch);
+    let file_out = format!("target/batch_{}_{}.bin", process::id(), batch);
+
+    let written = File::create(&file_in).and_then(|mut file| file.write_all(source(tool, statements, pending).as_bytes()));
+
+    if let Err(err) = written {
+        let _ = fs::remove_file(&file_in);
+        return Err(BatchError::Failed(format!("unable to write {}: {}", file_in, err)));
+    }
+
+    let result = tool.run(cpu, &file_in, &file_out);
+    let mut binary = Vec::new();
+
+    let read = match result {
+        Ok(Ok(())) => File::open(&file_out).and_then(|mut file| file.read_to_end(&mut binary)).map(|_| ()),
+        _ => Ok(()),
+    };
+
+    let _ = fs::remove_file(&file_in);
+    let _ = fs::remove_file(&file_out);
+
+    if let Err(err) = read {
+        return Err(BatchError::Failed(format!("unable to read the output of {} from {}: {}", tool.name(), file_out, err)));
+    }
+
+    match result {
+        Err(err) => Err(BatchError::Failed(format!("unable to run {}: {}", tool.name(), err))),
+        Ok(Err(stderr)) => {
+            let rejected = rejected_statements(tool, &stderr, pending);
+
+            match rejected.is_empty() {
+                true => Err(BatchError::Failed(format!("{} failed without an error for any statement: {}",
+                                                       tool.name(), stderr.trim()))),
+                false => Err(BatchError::Rejected(rejected)),
+            }
+        }
+        Ok(Ok(())) => split_code(&binary, pending.len())
+            .ok_or(BatchError::Failed(format!("the output of {} doesn't match the statement lengths", tool.name()))),
+    }
+}
+
+// Code of every statement or why the tool rejected it
+fn assemble_batches<T: Tool>(tool: &T, cpu: Cpu, statements: &[&str]) -> Vec<Assembled> {
+    let mut code = vec![Err(Rejection::new(Category::ToolFailure, "not assembled")); statements.len()];
+    let mut pending: Vec<usize> = (0..statements.len()).collect();
+    let mut batch = 0;
+
+    while !pending.is_empty() {
+        batch += 1;
+
+        match run(tool, cpu, statements, &pending, batch) {
+            Ok(parts) => {
+                for (&i, part) in pending.iter().zip(parts) {
+                    code[i] = Ok(part);
+                }
+
+                break;
+            }
+            Err(BatchError::Rejected(rejected)) => {
+                for &(n, ref messages) in &rejected {
+                    let i = pending[n];
+                    code[i] = Err(Rejection::new(categorize(&messages[0], statements[i]), &messages.join("; ")));
+                }
+
+                pending = pending.iter().enumerate()
+                    .filter(|&(n, _)| !rejected.iter().any(|&(m, _)| m == n))
+                    .map(|(_, &i)| i)
+                    .collect();
+            }
+            Err(BatchError::Failed(message)) => {
+                for &i in &pending {
+                    code[i] = Err(Rejection::new(Category::ToolFailure, &message));
+                }
+
+                break;
+            }
+        }
+    }
+
+    code
+}
+
+// Machine code as hex words, e.g. d050 0002
+fn describe_code(code: &Assembled) -> String {
+    match *code {
+        Ok(ref code) => code.chunks(2)
+            .map(|word| word.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join(" "),
+        Err(ref rejection) => format!("rejected ({})", rejection.category.name()),
+    }
+}
+
+pub fn create(config: &Config) -> Box<dyn Assembler> {
+    let path = config.path.clone();
+    let flags = config.flags.clone();
+
+    match config.backend {
+        Backend::Vasm => Box::new(Vasm { path: path.unwrap_or(VASM_EXE.to_owned()), flags }),
+        Backend::Gas(syntax) => Box::new(Gas::new(syntax, path, flags)),
+        Backend::Builtin => Box::new(Builtin),
+        Backend::Fake => Box::new(Fake),
+    }
+}
+
+impl Selection {
+    pub fn new(config: &Config, cross_check: bool) -> Selection {
+        // The built-in encoder has no use for the path and flags, with it they are the vasm's
+        let reference = match (cross_check, config.backend) {
+            (true, Backend::Builtin) => {
+                Some(create(&Config { backend: Backend::Vasm, path: config.path.clone(), flags: config.flags.clone() }))
+            }
+            (true, _) => Some(create(&Config { backend: Backend::Builtin, path: None, flags: Vec::new() })),
+            (false, _) => None,
+        };
+
+        Selection {
+            assembler: create(config),
+            reference,
+            flags: config.flags.clone(),
+            identity: None,
+            compared: 0,
+            mismatches: 0,
+        }
+    }
+
+    // Backend, version and flags of the assembler. The version runs the tool, so it's only
+    // asked for once the cache needs it
+    fn identity(&mut self) -> String {
+        if self.identity.is_none() {
+            let mut identity = format!("{} {}", self.assembler.name(), self.assembler.version());
+
+            for flag in &self.flags {
+                identity.push_str(&format!(" {}", flag));
+            }
+
+            self.identity = Some(identity);
+        }
+
+        self.identity.clone().unwrap_or_default()
+    }
+
+    fn cross_check(&mut self, statements: &[&str], code: &[Assembled], reference: &[Assembled]) {
+        self.compared += statements.len();
+
+        let reference_name = self.reference.as_ref().map_or("", |assembler| assembler.name());
+
+        for ((statement, code), reference) in statements.iter().zip(code).zip(reference) {
+            if code.as_ref().ok() != reference.as_ref().ok() {
+                self.mismatches += 1;
+                eprintln!("cross-check: '{}': {} {}, {} {}", statement, self.assembler.name(), describe_code(code),
+                          reference_name, describe_code(reference));
+            }
+        }
+    }
+
+    // Code of every statement with the selected assembler or why it was rejected. Rejections
+    // go to the diagnostics. Statements in the cache aren't assembled again
+    pub(crate) fn assemble(&mut self, cpu: Cpu, statements: &[&str], cache: Option<&mut Cache>,
+                           diagnostics: &mut Diagnostics) -> Vec<Assembled> {
+        let code = match cache {
+            Some(cache) => {
+                let identity = self.identity();
+                let mut code = cache.lookup_assembled(cpu, &identity, statements);
+                let missing: Vec<&str> = statements.iter().zip(&code)
+                    .filter(|&(_, code)| code.is_none())
+                    .map(|(&statement, _)| statement)
+                    .collect();
+
+                let mut assembled = self.assembler.assemble(cpu, &missing).into_iter();
+                cache.store_assembled(cpu, &identity, &missing, assembled.as_slice());
+
+                code.iter_mut()
+                    .map(|code| code.take().unwrap_or_else(|| assembled.next().unwrap()))
+                    .collect()
+            }
+            None => self.assembler.assemble(cpu, statements),
+        };
+
+        for (statement, code) in statements.iter().zip(&code) {
+            if let Err(ref rejection) = *code {
+                diagnostics.record(cpu, statement, rejection);
+            }
+        }
+
+        let reference = self.reference.as_ref().map(|reference| reference.assemble(cpu, statements));
+
+        if let Some(reference) = reference {
+            self.cross_check(statements, &code, &reference);
+        }
+
+        code
+    }
+
+    // Totals of the cross-check, None if it wasn't enabled
+    pub fn cross_check_summary(&self) -> Option<String> {
+        self.reference.as_ref().map(|_| {
+            format!("cross-check: {} statements compared, {} mismatches", self.compared, self.mismatches)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A batch of statements, each on the second of its three source lines (5, 8, 11, ...)
+    const STATEMENTS: &[&str] = &[
+        "abcd.b d0,d1",
+        "abcd.b d0,a0",
+        "abcd.w d0,d1",
+        "foo d0",
+        "extb.l d0",
+        "move.w d9,d0",
+        "move.w (8,a0,d0.l*4),d1",
+    ];
+
+    fn categories<T: Tool>(tool: &T, stderr: &str) -> Vec<(usize, Category)> {
+        let pending: Vec<usize> = (0..STATEMENTS.len()).collect();
+
+        rejected_statements(tool, stderr, &pending).into_iter()
+            .map(|(n, messages)| (n, categorize(&messages[0], STATEMENTS[n])))
+            .collect()
+    }
+
+    #[test]
+    fn vasm_categories() {
+        let vasm = Vasm { path: VASM_EXE.to_owned(), flags: Vec::new() };
+
+        let stderr = "\
+error 1 in line 5 of \"target/batch_1234_1.s\": illegal operand types
+>  abcd.b d0,a0
+
+error 2036 in line 8 of \"target/batch_1234_1.s\": illegal opcode extension
+>  abcd.w d0,d1
+
+error 2 in line 11 of \"target/batch_1234_1.s\": unknown mnemonic <foo>
+>  foo d0
+
+error 2001 in line 14 of \"target/batch_1234_1.s\": instruction not supported on selected architecture
+>  extb.l d0
+
+error 9 in line 17 of \"target/batch_1234_1.s\": undefined symbol <d9>
+>  move.w d9,d0
+";
+
+        assert_eq!(categories(&vasm, stderr).iter().map(|&(n, category)| (n, category.key())).collect::<Vec<_>>(),
+                   vec![(1, "illegal_ea"), (2, "unsupported_size"), (3, "syntax_error"), (4, "syntax_error"),
+                        (5, "syntax_error")]);
+    }
+
+    #[test]
+    fn gas_categories() {
+        let gas = Gas::new(Syntax::Motorola, None, Vec::new());
+
+        let stderr = "\
+target/batch_1234_1.s: Assembler messages:
+target/batch_1234_1.s:5: Error: operands mismatch -- statement `abcd.b %d0,%a0' ignored
+target/batch_1234_1.s:8: Error: Unknown operator -- statement `abcd.w %d0,%d1' ignored
+target/batch_1234_1.s:11: Error: Unknown operator -- statement `foo %d0' ignored
+target/batch_1234_1.s:14: Error: invalid instruction for this architecture; needs 68020 or higher \
+(68020 [68k, 68ec020], 68030 [68ec030], 68040 [68ec040], 68060 [68ec060]) -- statement `extb.l %d0' ignored
+target/batch_1234_1.s:20: Error: invalid operand mode for this architecture; needs 68020 or higher \
+-- statement `move.w (8,%a0,%d0.l*4),%d1' ignored
+";
+
+        assert_eq!(categories(&gas, stderr).iter().map(|&(n, category)| (n, category.key())).collect::<Vec<_>>(),
+                   vec![(1, "illegal_ea"), (2, "unsupported_size"), (3, "syntax_error"), (4, "syntax_error"),
+                        (6, "illegal_ea")]);
+    }
+
+    #[test]
+    fn errors_of_other_lines() {
+        let vasm = Vasm { path: VASM_EXE.to_owned(), flags: Vec::new() };
+
+        // An error on a label counts for its statement, errors on the length words and warnings don't
+        let stderr = "\
+error 2 in line 4 of \"target/batch_1234_1.s\": unknown mnemonic <begin_1>
+error 2 in line 22 of \"target/batch_1234_1.s\": unknown mnemonic <dc.w>
+warning 2047 in line 2 of \"target/batch_1234_1.s\": size of operand ignored
+";
+
+        assert_eq!(categories(&vasm, stderr).iter().map(|&(n, _)| n).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn split_code_by_lengths() {
+        // nop, moveq #1,d0, add.w #1,d0 and the lengths 2, 2 and 4
+        let binary = [0x4e, 0x71, 0x70, 0x01, 0xd0, 0x7c, 0x00, 0x01, 0x00, 0x02, 0x00, 0x02, 0x00, 0x04];
+
+        assert_eq!(split_code(&binary, 3),
+                   Some(vec![vec![0x4e, 0x71], vec![0x70, 0x01], vec![0xd0, 0x7c, 0x00, 0x01]]));
+    }
+
+    #[test]
+    fn split_code_without_code() {
+        assert_eq!(split_code(&[], 0), Some(Vec::new()));
+        assert_eq!(split_code(&[0x00, 0x00], 1), Some(vec![Vec::new()]));
+        assert_eq!(split_code(&[0x4e, 0x71, 0x00, 0x00, 0x00, 0x02], 2), Some(vec![Vec::new(), vec![0x4e, 0x71]]));
+    }
+
+    #[test]
+    fn split_code_rejects_mismatched_lengths() {
+        // Shorter than the length words
+        assert_eq!(split_code(&[0x00, 0x02], 2), None);
+        // Lengths beyond the code
+        assert_eq!(split_code(&[0x4e, 0x71, 0x00, 0x04], 1), None);
+        // Code left over after the last statement
+        assert_eq!(split_code(&[0x4e, 0x71, 0x4e, 0x71, 0x00, 0x02], 1), None);
+    }
+}
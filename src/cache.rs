@@ -0,0 +1,638 @@
+// Persistent on-disk cache of assembled statements and measurements.
+//
+// Assembled statements are keyed by the CPU, the assembler backend and its version and
+// the statement text. The version of the built-in encoder includes a digest of its source,
+// so the entries of an older encoder aren't used. Measurements are keyed by the emulator
+// (CPU, RAM size and a digest of its C sources), the table and a digest of the statements,
+// code and presets of the batch, since the instructions of a batch share the RAM and one
+// can see the writes of another.
+//
+// Entries go to two text files in the cache directory, one line each, and new ones are
+// appended at the end of the run. A later line overrides an earlier one with the same
+// key. Files with another format line are ignored and rewritten.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use assembler::{Assembled, Category, Rejection};
+use cpu::Cpu;
+use {Access, BuildResult, BusCount, Fault};
+
+// First line of both files, bump the number when the line format changes
+const FORMAT: &str = "68k_documentation_gen cache 2";
+
+const ASSEMBLED_FILE: &str = "assembled.txt";
+const MEASURED_FILE: &str = "measured.txt";
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Mode {
+    // Take entries from the cache and add the missing ones
+    Use,
+    // Assemble and measure everything and compare the cached entries with the fresh results
+    Verify,
+}
+
+// Entries of one cache file
+struct Store {
+    path: PathBuf,
+    entries: HashMap<String, String>,
+    // Entries to append when the cache is saved
+    added: Vec<(String, String)>,
+    // False if the file is missing or has another format, it's rewritten then
+    valid: bool,
+}
+
+impl Store {
+    fn load(path: PathBuf) -> io::Result<Store> {
+        let mut store = Store { path, entries: HashMap::new(), added: Vec::new(), valid: false };
+
+        let file = match File::open(&store.path) {
+            Ok(file) => file,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(err) => return Err(err),
+        };
+
+        let mut lines = BufReader::new(file).lines();
+
+        match lines.next() {
+            Some(Ok(ref line)) if line == FORMAT => store.valid = true,
+            _ => return Ok(store),
+        }
+
+        for line in lines {
+            let line = line?;
+
+            // The value follows the last tab, keys are tab separated fields
+            if let Some(split) = line.rfind('\t') {
+                store.entries.insert(line[..split].to_owned(), line[split + 1..].to_owned());
+            }
+        }
+
+        Ok(store)
+    }
+
+    fn insert(&mut self, key: String, value: String) {
+        if self.entries.get(&key) != Some(&value) {
+            self.entries.insert(key.clone(), value.clone());
+            self.added.push((key, value));
+        }
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        if self.valid && self.added.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = match self.valid {
+            true => OpenOptions::new().append(true).open(&self.path)?,
+            false => {
+                let mut file = File::create(&self.path)?;
+                writeln!(file, "{}", FORMAT)?;
+                file
+            }
+        };
+
+        for (key, value) in &self.added {
+            writeln!(file, "{}\t{}", key, value)?;
+        }
+
+        self.valid = true;
+        self.added.clear();
+        Ok(())
+    }
+}
+
+pub struct Cache {
+    mode: Mode,
+    assembled: Store,
+    measured: Store,
+    // CPU, RAM size and source digest of the emulator, see set_emulator()
+    emulator: String,
+    verified: usize,
+    mismatches: usize,
+}
+
+impl Cache {
+    // Opens the cache in dir, which is created if needed. clear removes the cached entries first
+    pub fn open(dir: &str, mode: Mode, clear: bool) -> io::Result<Cache> {
+        let dir = Path::new(dir);
+        fs::create_dir_all(dir)?;
+
+        if clear {
+            for file in &[ASSEMBLED_FILE, MEASURED_FILE] {
+                match fs::remove_file(dir.join(file)) {
+                    Err(ref err) if err.kind() != io::ErrorKind::NotFound => {
+                        return Err(io::Error::new(err.kind(), err.to_string()))
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(Cache {
+            mode,
+            assembled: Store::load(dir.join(ASSEMBLED_FILE))?,
+            measured: Store::load(dir.join(MEASURED_FILE))?,
+            emulator: String::new(),
+            verified: 0,
+            mismatches: 0,
+        })
+    }
+
+    // Called whenever the emulator is set up for another CPU. build.rs digests the sources
+    // in native/, so a changed emulator doesn't get the measurements of the old one
+    pub fn set_emulator(&mut self, cpu: Cpu, ram_size: usize) {
+        self.emulator = format!("{}\tram={}\tnative={}", cpu.name(), ram_size, env!("NATIVE_DIGEST"));
+    }
+
+    // Appends the new entries to the cache files
+    pub fn save(&mut self) -> io::Result<()> {
+        self.assembled.save()?;
+        self.measured.save()
+    }
+
+    // Verified entries and mismatches, None unless verifying
+    pub fn verify_summary(&self) -> Option<(usize, usize)> {
+        match self.mode {
+            Mode::Verify => Some((self.verified, self.mismatches)),
+            Mode::Use => None,
+        }
+    }
+
+    // Counts the check of a fresh value against the cached one of key, if there is one
+    fn verify(&mut self, what: &str, cached: Option<&String>, value: &str) {
+        if let Some(cached) = cached {
+            self.verified += 1;
+
+            if cached != value {
+                self.mismatches += 1;
+                eprintln!("cache: {} differs from a fresh run", what);
+            }
+        }
+    }
+
+    // Cached code of every statement, None for the ones that aren't cached. assembler
+    // identifies the backend and its version. Nothing is taken from the cache when verifying
+    pub(crate) fn lookup_assembled(&self, cpu: Cpu, assembler: &str, statements: &[&str]) -> Vec<Option<Assembled>> {
+        match self.mode {
+            Mode::Use => {
+                statements.iter()
+                    .map(|statement| {
+                        let key = format!("{}\t{}\t{}", cpu.name(), assembler, sanitize(statement));
+                        self.assembled.entries.get(&key).and_then(|value| decode_assembled(value))
+                    })
+                    .collect()
+            }
+            Mode::Verify => vec![None; statements.len()],
+        }
+    }
+
+    pub(crate) fn store_assembled(&mut self, cpu: Cpu, assembler: &str, statements: &[&str], code: &[Assembled]) {
+        for (statement, code) in statements.iter().zip(code) {
+            // Tool failures say nothing about the statement
+            if let Err(Rejection { category: Category::ToolFailure, .. }) = *code {
+                continue;
+            }
+
+            let key = format!("{}\t{}\t{}", cpu.name(), assembler, sanitize(statement));
+            let value = encode_assembled(code);
+
+            if self.mode == Mode::Verify {
+                let cached = self.assembled.entries.get(&key).cloned();
+                self.verify(&format!("assembled statement '{}'", statement), cached.as_ref(), &value);
+            }
+
+            self.assembled.insert(key, value);
+        }
+    }
+
+    // Measures a batch of statements with run, or takes the measurements from the cache
+    pub(crate) fn measure<F>(&mut self, table: &str, statements: &mut [BuildResult], run: F)
+        where F: FnOnce(&mut [BuildResult])
+    {
+        let key = format!("{}\t{}\t{}", self.emulator, sanitize(table), batch_digest(statements));
+        let cached = self.measured.entries.get(&key).cloned();
+
+        if self.mode == Mode::Use {
+            if let Some(ref cached) = cached {
+                if decode_measurements(cached, statements) {
+                    return;
+                }
+            }
+        }
+
+        run(statements);
+
+        let value = encode_measurements(statements);
+
+        if self.mode == Mode::Verify {
+            self.verify(&format!("measurement of table {}", table), cached.as_ref(), &value);
+        }
+
+        self.measured.insert(key, value);
+    }
+}
+
+fn sanitize(text: &str) -> String {
+    text.replace(['\t', '\n'], " ").replace('\r', "")
+}
+
+fn encode_assembled(code: &Assembled) -> String {
+    match *code {
+        Ok(ref bytes) => format!("ok {}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+        Err(ref rejection) => format!("rejected {} {}", rejection.category.key(), sanitize(&rejection.message)),
+    }
+}
+
+fn decode_assembled(text: &str) -> Option<Assembled> {
+    let mut parts = text.splitn(3, ' ');
+
+    match (parts.next(), parts.next()) {
+        (Some("ok"), hex) => {
+            let hex = hex.unwrap_or("");
+
+            if hex.len() % 2 != 0 {
+                return None;
+            }
+
+            (0..hex.len()).step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+                .collect::<Option<Vec<u8>>>()
+                .map(Ok)
+        }
+        (Some("rejected"), Some(key)) => {
+            let category = Category::all().iter().cloned().find(|category| category.key() == key)?;
+            Some(Err(Rejection { category, message: parts.next().unwrap_or("").to_owned() }))
+        }
+        _ => None,
+    }
+}
+
+// 64 bit FNV-1a, stable across runs and Rust versions unlike the std hashers
+struct Digest(u64);
+
+impl Digest {
+    fn new() -> Digest {
+        Digest(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&[(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8]);
+    }
+}
+
+// Digest of some bytes as 16 hex digits
+pub(crate) fn digest(bytes: &[u8]) -> String {
+    let mut digest = Digest::new();
+    digest.write(bytes);
+    format!("{:016x}", digest.0)
+}
+
+// Everything that goes into running a batch: statements, code and presets
+fn batch_digest(statements: &[BuildResult]) -> String {
+    let mut digest = Digest::new();
+
+    for statement in statements {
+        digest.write(statement.statement.as_bytes());
+        digest.write(&[0]);
+
+        match (statement.cycle_count, statement.preset) {
+            (Some(_), Some(preset)) => {
+                digest.write_u32(statement.code.len() as u32);
+                digest.write(&statement.code);
+
+                for &reg in preset.d.iter().chain(&preset.a) {
+                    digest.write_u32(reg);
+                }
+
+                digest.write_u32(preset.sr);
+            }
+            // Not run
+            _ => digest.write(&[0xff]),
+        }
+    }
+
+    format!("{:016x}", digest.0)
+}
+
+// One space separated entry per statement: - if it wasn't run, m<cycles>,<reads>,<writes>
+// if it was measured and f<address>,<size>,<write>,<pc> if it faulted, each followed by
+// /<address>,<size>,<write>,<fc>,<split> for every access of the trace
+fn encode_measurements(statements: &[BuildResult]) -> String {
+    let entries: Vec<String> = statements.iter().map(|statement| {
+        let mut entry = match (statement.cycle_count, statement.bus, statement.fault) {
+            (Some(cycles), Some(bus), _) => format!("m{},{},{}", cycles, bus.reads, bus.writes),
+            (None, _, Some(fault)) => format!("f{},{},{},{}", fault.address, fault.size, fault.write, fault.pc),
+            _ => return "-".to_owned(),
+        };
+
+        for access in &statement.trace {
+            entry.push_str(&format!("/{},{},{},{},{}", access.address, access.size, access.write, access.fc, access.split));
+        }
+
+        entry
+    }).collect();
+
+    entries.join(" ")
+}
+
+fn numbers(text: &str) -> Option<Vec<u32>> {
+    text.split(',').map(|n| n.parse::<u32>().ok()).collect()
+}
+
+// Applies cached measurements to statements that are about to be run, false if they don't fit
+fn decode_measurements(text: &str, statements: &mut [BuildResult]) -> bool {
+    let entries: Vec<&str> = text.split(' ').collect();
+
+    if entries.len() != statements.len() {
+        return false;
+    }
+
+    let mut results = Vec::with_capacity(entries.len());
+
+    for (entry, statement) in entries.iter().zip(statements.iter()) {
+        let run = statement.cycle_count.is_some();
+
+        if *entry == "-" {
+            if run {
+                return false;
+            }
+
+            results.push(None);
+            continue;
+        }
+
+        let mut parts = entry.split('/');
+        let head = parts.next().unwrap_or("");
+
+        let (kind, values) = match (head.chars().next(), numbers(head.get(1..).unwrap_or(""))) {
+            (Some(kind), Some(values)) if run && values.len() >= 3 => (kind, values),
+            _ => return false,
+        };
+
+        let trace = match parts.map(numbers).collect::<Option<Vec<Vec<u32>>>>() {
+            Some(ref trace) if trace.iter().all(|access| access.len() == 5) => {
+                trace.iter().map(|a| Access { address: a[0], size: a[1], write: a[2], fc: a[3], split: a[4] }).collect()
+            }
+            _ => return false,
+        };
+
+        match kind {
+            'm' => results.push(Some((Some(values[0] as usize), Some(BusCount { reads: values[1], writes: values[2] }), None, trace))),
+            'f' if values.len() == 4 => {
+                let fault = Fault { faulted: 1, address: values[0], size: values[1], write: values[2], pc: values[3] };
+                results.push(Some((None, None, Some(fault), trace)));
+            }
+            _ => return false,
+        }
+    }
+
+    for (statement, result) in statements.iter_mut().zip(results) {
+        if let Some((cycle_count, bus, fault, trace)) = result {
+            statement.cycle_count = cycle_count;
+            statement.bus = bus;
+            statement.fault = fault;
+            statement.trace = trace;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+    use {Op, Preset};
+
+    fn statement(text: &str, code: &[u8], preset: Option<Preset>) -> BuildResult {
+        BuildResult {
+            src: None,
+            dst: Op::new("", ""),
+            statement: text.to_owned(),
+            code: code.to_vec(),
+            preset,
+            cycle_count: preset.map(|_| 0),
+            bus: None,
+            fault: None,
+            trace: Vec::new(),
+            cases: None,
+            rejection: None,
+        }
+    }
+
+    // Spelled out so the expected digest doesn't follow changes of the default preset
+    const PRESET: Preset = Preset { d: [1; 8], a: [0x800; 8], sr: 0x2700 };
+
+    // A measured NOP, a faulting read and a statement without a preset
+    fn batch() -> Vec<BuildResult> {
+        vec![
+            statement("nop", &[0x4e, 0x71], Some(PRESET)),
+            statement("move.w $fffff0.l,d0", &[0x30, 0x39, 0x00, 0xff, 0xff, 0xf0], Some(PRESET)),
+            statement("bsr *+2", &[], None),
+        ]
+    }
+
+    fn measured_batch() -> Vec<BuildResult> {
+        let mut statements = batch();
+
+        statements[0].cycle_count = Some(4);
+        statements[0].bus = Some(BusCount { reads: 1, writes: 0 });
+        statements[0].trace = vec![Access { address: 0x1002, size: 2, write: 0, fc: 6, split: 0 }];
+
+        statements[1].cycle_count = None;
+        statements[1].fault = Some(Fault { faulted: 1, address: 0xfffff0, size: 2, write: 0, pc: 0x1000 });
+        statements[1].trace = vec![Access { address: 0x1002, size: 2, write: 0, fc: 6, split: 0 },
+                                   Access { address: 0x1004, size: 2, write: 0, fc: 6, split: 0 }];
+
+        statements
+    }
+
+    // Stands in for the emulator with the measurements of measured_batch()
+    fn run(statements: &mut [BuildResult]) {
+        for (statement, measured) in statements.iter_mut().zip(measured_batch()) {
+            statement.cycle_count = measured.cycle_count;
+            statement.bus = measured.bus;
+            statement.fault = measured.fault;
+            statement.trace = measured.trace;
+        }
+    }
+
+    fn temp_dir(name: &str) -> String {
+        let dir = env::temp_dir().join(format!("check68k_{}_{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn fnv_1a_vectors() {
+        assert_eq!(digest(b""), "cbf29ce484222325");
+        assert_eq!(digest(b"a"), "af63dc4c8601ec8c");
+        assert_eq!(digest(b"foobar"), "85944171f73967e8");
+    }
+
+    #[test]
+    fn batch_digest_is_stable() {
+        // Cached measurements are only found again if this never changes for the same batch
+        assert_eq!(batch_digest(&batch()), batch_digest(&batch()));
+        assert_eq!(batch_digest(&batch()), "ccfbd2036519c721");
+
+        // The measured cycles aren't part of it, only what goes into the run
+        let mut measured = batch();
+        run(&mut measured[..1]);
+        assert_eq!(batch_digest(&measured), batch_digest(&batch()));
+
+        let mut changed = batch();
+        changed[0].preset.as_mut().unwrap().d[0] = 2;
+        assert!(batch_digest(&changed) != batch_digest(&batch()));
+
+        let mut changed = batch();
+        changed[1].code[5] = 0xf2;
+        assert!(batch_digest(&changed) != batch_digest(&batch()));
+    }
+
+    #[test]
+    fn assembled_round_trip() {
+        let entries = vec![
+            Ok(vec![0x4e, 0x71]),
+            Ok(Vec::new()),
+            Err(Rejection { category: Category::IllegalEa, message: "illegal operand types".to_owned() }),
+            Err(Rejection { category: Category::Syntax, message: "unknown mnemonic <foo>".to_owned() }),
+        ];
+
+        for code in &entries {
+            let text = encode_assembled(code);
+            let decoded = decode_assembled(&text).expect("decodes");
+            assert_eq!(encode_assembled(&decoded), text);
+        }
+
+        assert_eq!(encode_assembled(&entries[0]), "ok 4e71");
+        assert_eq!(encode_assembled(&entries[2]), "rejected illegal_ea illegal operand types");
+
+        // The message can't break the line format
+        let rejection = Err(Rejection { category: Category::UnsupportedSize, message: "a\tb\nc".to_owned() });
+        assert_eq!(encode_assembled(&rejection), "rejected unsupported_size a b c");
+
+        assert!(decode_assembled("ok 4e7").is_none());
+        assert!(decode_assembled("ok 4g71").is_none());
+        assert!(decode_assembled("rejected no_such_category x").is_none());
+        assert!(decode_assembled("measured 4").is_none());
+    }
+
+    #[test]
+    fn measurements_round_trip() {
+        let text = encode_measurements(&measured_batch());
+        assert_eq!(text, "m4,1,0/4098,2,0,6,0 f16777200,2,0,4096/4098,2,0,6,0/4100,2,0,6,0 -");
+
+        let mut statements = batch();
+        assert!(decode_measurements(&text, &mut statements));
+        assert_eq!(encode_measurements(&statements), text);
+
+        assert_eq!(statements[0].cycle_count, Some(4));
+        assert_eq!(statements[1].cycle_count, None);
+        assert_eq!(statements[1].fault.map(|fault| fault.address), Some(0xfffff0));
+        assert_eq!(statements[2].cycle_count, None);
+    }
+
+    #[test]
+    fn measurements_that_dont_fit() {
+        // Another number of statements
+        assert!(!decode_measurements("m4,1,0 -", &mut batch()));
+        // Not run although it has a preset
+        assert!(!decode_measurements("- - -", &mut batch()));
+        // Measured although it isn't run
+        assert!(!decode_measurements("m4,1,0 m4,1,0 m4,1,0", &mut batch()));
+        // Broken trace
+        assert!(!decode_measurements("m4,1,0/1,2 m4,1,0 -", &mut batch()));
+        // Access without the split flag
+        assert!(!decode_measurements("m4,1,0/4098,2,0,6 m4,1,0 -", &mut batch()));
+
+        // Nothing is applied if any entry doesn't fit
+        let mut statements = batch();
+        assert!(!decode_measurements("m4,1,0 x -", &mut statements));
+        assert_eq!(statements[0].cycle_count, Some(0));
+    }
+
+    #[test]
+    fn assembled_entries_on_disk() {
+        let dir = temp_dir("assembled");
+        let statements = ["nop", "abcd.b d0,a0", "moveq #1,d0"];
+        let code = vec![
+            Ok(vec![0x4e, 0x71]),
+            Err(Rejection { category: Category::IllegalEa, message: "illegal operand types".to_owned() }),
+            Err(Rejection { category: Category::ToolFailure, message: "unable to run vasm".to_owned() }),
+        ];
+
+        let mut cache = Cache::open(&dir, Mode::Use, true).unwrap();
+        assert!(cache.lookup_assembled(Cpu::M68000, "vasm 1.9", &statements).iter().all(Option::is_none));
+        cache.store_assembled(Cpu::M68000, "vasm 1.9", &statements, &code);
+        cache.save().unwrap();
+
+        let cache = Cache::open(&dir, Mode::Use, false).unwrap();
+        let cached: Vec<Option<String>> = cache.lookup_assembled(Cpu::M68000, "vasm 1.9", &statements).iter()
+            .map(|code| code.as_ref().map(encode_assembled))
+            .collect();
+
+        // Tool failures aren't cached
+        assert_eq!(cached, vec![Some("ok 4e71".to_owned()),
+                                Some("rejected illegal_ea illegal operand types".to_owned()),
+                                None]);
+
+        // Keyed on the assembler and the CPU
+        assert!(cache.lookup_assembled(Cpu::M68000, "vasm 2.0", &statements)[0].is_none());
+        assert!(cache.lookup_assembled(Cpu::M68010, "vasm 1.9", &statements)[0].is_none());
+
+        // Clearing starts over
+        let cache = Cache::open(&dir, Mode::Use, true).unwrap();
+        assert!(cache.lookup_assembled(Cpu::M68000, "vasm 1.9", &statements)[0].is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn measurements_on_disk() {
+        let dir = temp_dir("measured");
+
+        let mut cache = Cache::open(&dir, Mode::Use, true).unwrap();
+        cache.set_emulator(Cpu::M68000, 0x100000);
+        cache.measure("nop", &mut batch(), run);
+        cache.save().unwrap();
+
+        let mut cache = Cache::open(&dir, Mode::Use, false).unwrap();
+        cache.set_emulator(Cpu::M68000, 0x100000);
+
+        let mut statements = batch();
+        cache.measure("nop", &mut statements, |_| panic!("measured again"));
+        assert_eq!(encode_measurements(&statements), encode_measurements(&measured_batch()));
+
+        // Another emulator configuration is measured again
+        let mut measured = false;
+        cache.set_emulator(Cpu::M68000, 0x10000);
+        cache.measure("nop", &mut batch(), |statements| { measured = true; run(statements) });
+        assert!(measured);
+
+        // Verifying measures everything and counts the entries that differ
+        let mut cache = Cache::open(&dir, Mode::Verify, false).unwrap();
+        cache.set_emulator(Cpu::M68000, 0x100000);
+        cache.measure("nop", &mut batch(), run);
+        assert_eq!(cache.verify_summary(), Some((1, 0)));
+
+        cache.measure("nop", &mut batch(), |statements| {
+            run(statements);
+            statements[0].cycle_count = Some(6);
+        });
+        assert_eq!(cache.verify_summary(), Some((2, 1)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
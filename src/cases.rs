@@ -0,0 +1,77 @@
+// Best, cache and worst case timings of the 68020, the way its user manual lists them.
+//
+// Musashi doesn't model the instruction cache or the pipeline. Its 68020 cycle tables
+// hold the cache case (the instruction is in the cache), so that is what gets measured.
+// The best case (the operand fetch fully overlapped) and the worst case (extension words
+// fetched from memory, no overlap) are estimated: they differ from it by the fetch
+// effective address time of the fetched operand, taken from the same manual table
+// Musashi's EA cycles come from. Operands that table doesn't cover get no estimate.
+
+use descriptions::Size;
+use {BuildResult, Table, TableLayout};
+
+// best and worst are None if the operand's fetch time isn't known
+#[derive(Copy, Clone)]
+pub struct Cases {
+    pub best: Option<usize>,
+    pub cache: usize,
+    pub worst: Option<usize>,
+}
+
+// Best, cache and worst case fetch effective address time of a mode, the cache case
+// matches g_ea_cycle_table in native/m68kmake.c. The scaled index modes use the brief
+// extension word like d(An,Dn). The full format modes ((bd,An,Xn) and the memory indirect
+// ones) add cycles for every base and outer displacement size that the overlap can't be
+// estimated for, so they get no best and worst case
+fn fetch_times(mode: &str, long: bool) -> Option<(usize, usize, usize)> {
+    match mode {
+        "Dn" | "An" => Some((0, 0, 0)),
+        "(An)" => Some((3, 4, 4)),
+        "(An)+" => Some((4, 4, 4)),
+        "-(An)" => Some((3, 5, 5)),
+        "d(An)" | "d(PC)" => Some((3, 5, 6)),
+        "d(An,Dn)" | "d(PC,Dn)" | "(d8,An,Dn.L*4)" | "(d8,PC,Dn.L*4)" => Some((4, 7, 8)),
+        "xxx.W" => Some((3, 4, 6)),
+        "xxx.L" => Some((3, 4, 7)),
+        "#xxx" if long => Some((0, 4, 5)),
+        "#xxx" => Some((0, 2, 3)),
+        _ => None,
+    }
+}
+
+// The source is the fetched operand, or the only one if there is no source
+fn cases(result: &BuildResult, long: bool) -> Option<Cases> {
+    let mode = result.src.unwrap_or(result.dst).print_name;
+    let times = fetch_times(mode, long);
+
+    result.cycle_count.map(|cycles| {
+        Cases {
+            best: times.map(|(best, cache, _)| (cycles + best).saturating_sub(cache)),
+            cache: cycles,
+            worst: times.map(|(_, cache, worst)| cycles + worst - cache),
+        }
+    })
+}
+
+// Adds the cases to every measured cell, operand sweeps only list their cache case
+pub(crate) fn add_cases(tables: &mut [Table]) {
+    for table in tables {
+        if let Table::Measured(ref mut table) = *table {
+            if let TableLayout::Operands(..) = table.layout {
+                continue;
+            }
+
+            let long = table.size == Some(Size::Long);
+
+            for result in &mut table.statements {
+                result.cases = cases(result, long);
+            }
+        }
+    }
+}
+
+// Cell text of the cases, e.g. 3,5,6 or -,5,- without an estimate
+pub fn describe(cases: &Cases) -> String {
+    let estimate = |case: Option<usize>| case.map_or("-".to_owned(), |cycles| cycles.to_string());
+    format!("{},{},{}", estimate(cases.best), cases.cache, estimate(cases.worst))
+}
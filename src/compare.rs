@@ -0,0 +1,119 @@
+// Side-by-side comparison of the timings measured for several CPUs.
+//
+// Every cell lists the cycle counts in CPU order separated by '/', e.g. "8/8/4"
+// (or "8(2/0)/8(2/0)/4(1/0)" with bus cycles).
+// '-' marks a CPU that doesn't have the instruction or rejects the operands and
+// cells where the CPUs disagree are printed in bold. Operand sweeps are left out.
+
+use std::ptr;
+
+use {CellFormat, DocSet, Instruction, Table, TableLayout, TimingTable};
+use {measured_text, print_instruction_header, print_layout, print_predef_table};
+
+fn find_table<'a, 'b>(tables: &'b [Table<'a>], name: &str) -> Option<&'b TimingTable<'a>> {
+    tables.iter()
+        .filter_map(|table| match *table {
+            Table::Measured(ref table) if table.name == name => Some(table),
+            _ => None,
+        })
+        .next()
+}
+
+fn compare_cell(counts: &[Option<String>]) -> Option<String> {
+    if counts.iter().all(|c| c.is_none()) {
+        return None;
+    }
+
+    let text = counts.iter()
+        .map(|c| match *c {
+            Some(ref count) => count.clone(),
+            None => "-".to_owned(),
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    if counts.iter().all(|c| *c == counts[0]) {
+        Some(text)
+    } else {
+        Some(format!("**{}**", text))
+    }
+}
+
+fn compare_cells(tables: &[Option<&TimingTable>], format: CellFormat) -> Vec<Option<String>> {
+    let len = tables.iter().filter_map(|t| *t).map(|t| t.statements.len()).next().unwrap_or(0);
+
+    (0..len)
+        .map(|i| {
+            let counts: Vec<Option<String>> = tables.iter()
+                .map(|t| t.and_then(|t| measured_text(&t.statements[i], format)))
+                .collect();
+
+            compare_cell(&counts)
+        })
+        .collect()
+}
+
+// Tables of the first CPU that has them are used for the layout and the predefined tables,
+// which are the same for every CPU
+fn print_compared_tables(reference: &[Table], tables: &[Option<&Vec<Table>>], format: CellFormat) {
+    for table in reference {
+        match *table {
+            Table::Predefined(ref name, _, rows) => print_predef_table(name, rows),
+            // Operand sweeps have thousands of cells, they are only summarised per CPU
+            Table::Measured(TimingTable { layout: TableLayout::Operands(..), .. }) => (),
+            Table::Measured(ref table) => {
+                let measured: Vec<Option<&TimingTable>> = tables.iter()
+                    .map(|t| t.and_then(|t| find_table(t, &table.name)))
+                    .collect();
+
+                let cells = compare_cells(&measured, format);
+
+                if cells.iter().all(|c| c.is_none()) {
+                    continue;
+                }
+
+                print_layout(&table.name, &table.layout, &cells);
+            }
+        }
+    }
+}
+
+pub(crate) fn print_comparison(instructions: &[Instruction], sets: &[DocSet], format: CellFormat) {
+    let names: Vec<&str> = sets.iter().map(|set| set.cpu.name()).collect();
+    let titles: Vec<String> = names.iter().map(|name| format!("MC{}", name)).collect();
+
+    println!("# {} comparison\n", titles.join(" / "));
+    println!("Cells list the cycle counts as {}. `-` means the CPU doesn't have the instruction or rejects the \
+              operands. Cells where the CPUs differ are in bold.\n", names.join("/"));
+
+    if sets.iter().any(|set| set.cpu.has_instruction_cache()) {
+        println!("68020 cells give the cache case, documenting a single CPU lists best and worst case as well.\n");
+    }
+
+    for inst in instructions {
+        let tables: Vec<Option<&Vec<Table>>> = sets.iter()
+            .map(|set| set.instructions.iter().find(|doc| ptr::eq(doc.0, inst)).map(|doc| &doc.1))
+            .collect();
+
+        let reference = match tables.iter().filter_map(|t| *t).next() {
+            Some(reference) => reference,
+            None => continue,
+        };
+
+        print_instruction_header(inst);
+        print_compared_tables(reference, &tables, format);
+    }
+
+    if let Some(first) = sets.first() {
+        for chapter in &first.chapters {
+            let tables: Vec<Option<&Vec<Table>>> = sets.iter()
+                .map(|set| set.chapters.iter().find(|c| c.title == chapter.title).map(|c| &c.tables))
+                .collect();
+
+            println!("## {}\n", chapter.title);
+            println!("{}\n", chapter.text);
+
+            print_compared_tables(&chapter.tables, &tables, format);
+        }
+    }
+}
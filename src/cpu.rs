@@ -0,0 +1,60 @@
+// CPU types that Musashi can emulate. The order matters: an instruction
+// introduced on one CPU is available on every CPU after it.
+#[derive(Copy, Clone, PartialEq, PartialOrd)]
+pub enum Cpu {
+    M68000,
+    M68010,
+    M68EC020,
+    M68020,
+}
+
+impl Cpu {
+    pub fn all() -> &'static [Cpu] {
+        &[Cpu::M68000, Cpu::M68010, Cpu::M68EC020, Cpu::M68020]
+    }
+
+    pub fn from_name(name: &str) -> Option<Cpu> {
+        match name.to_lowercase().as_str() {
+            "68000" => Some(Cpu::M68000),
+            "68010" => Some(Cpu::M68010),
+            "68ec020" => Some(Cpu::M68EC020),
+            "68020" => Some(Cpu::M68020),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Cpu::M68000 => "68000",
+            Cpu::M68010 => "68010",
+            Cpu::M68EC020 => "68EC020",
+            Cpu::M68020 => "68020",
+        }
+    }
+
+    // Value of the matching M68K_CPU_TYPE_* in native/m68k.h
+    pub fn musashi_type(&self) -> u32 {
+        match *self {
+            Cpu::M68000 => 1,
+            Cpu::M68010 => 2,
+            Cpu::M68EC020 => 3,
+            Cpu::M68020 => 4,
+        }
+    }
+
+    // The 68020 manual gives best, cache and worst case timings because of the instruction cache
+    pub fn has_instruction_cache(&self) -> bool {
+        *self >= Cpu::M68EC020
+    }
+
+    // CPU flag of vasm and GNU as. The EC020 only differs from the 020 in its
+    // address bus width so it assembles the same instruction set
+    pub fn assembler_flag(&self) -> &'static str {
+        match *self {
+            Cpu::M68000 => "-m68000",
+            Cpu::M68010 => "-m68010",
+            Cpu::M68EC020 => "-m68020",
+            Cpu::M68020 => "-m68020",
+        }
+    }
+}
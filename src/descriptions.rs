@@ -15,6 +15,7 @@ impl Flag {
     }
 }
 
+#[derive(PartialEq)]
 pub struct FlagsDesc {
     pub x: Flag,
     pub n: Flag,
@@ -175,7 +176,89 @@ const FLAGS_TST: FlagsDesc = FlagsDesc {
     c: Flag::Clear("C — Always cleared."),
 };
 
+const FLAGS_SBCD: FlagsDesc = FlagsDesc {
+    x: Flag::Set("X — Set the same as carry"),
+    n: Flag::Undefined,
+    z: Flag::Clear("Z — Cleared if the result is nonzero; unchanged otherwise"),
+    v: Flag::Undefined,
+    c: Flag::Set("C — Set if a decimal borrow (from the most significant digit) was generated; cleared otherwise."),
+};
+
+const FLAGS_CHK: FlagsDesc = FlagsDesc {
+    x: Flag::NotAffected("X — Not Affected."),
+    n: Flag::Set("N — Set if Dn < 0; cleared if Dn > effective address operand; undefined otherwise."),
+    z: Flag::Undefined,
+    v: Flag::Undefined,
+    c: Flag::Undefined,
+};
+
+const FLAGS_ANDI_CCR: FlagsDesc = FlagsDesc {
+    x: Flag::Clear("X — Cleared if bit 4 of immediate operand is zero; unchanged otherwise."),
+    n: Flag::Clear("N — Cleared if bit 3 of immediate operand is zero; unchanged otherwise."),
+    z: Flag::Clear("Z — Cleared if bit 2 of immediate operand is zero; unchanged otherwise."),
+    v: Flag::Clear("V — Cleared if bit 1 of immediate operand is zero; unchanged otherwise."),
+    c: Flag::Clear("C — Cleared if bit 0 of immediate operand is zero; unchanged otherwise."),
+};
+
+const FLAGS_ORI_CCR: FlagsDesc = FlagsDesc {
+    x: Flag::Set("X — Set if bit 4 of immediate operand is one; unchanged otherwise."),
+    n: Flag::Set("N — Set if bit 3 of immediate operand is one; unchanged otherwise."),
+    z: Flag::Set("Z — Set if bit 2 of immediate operand is one; unchanged otherwise."),
+    v: Flag::Set("V — Set if bit 1 of immediate operand is one; unchanged otherwise."),
+    c: Flag::Set("C — Set if bit 0 of immediate operand is one; unchanged otherwise."),
+};
+
+const FLAGS_EORI_CCR: FlagsDesc = FlagsDesc {
+    x: Flag::Set("X — Changed if bit 4 of immediate operand is one; unchanged otherwise."),
+    n: Flag::Set("N — Changed if bit 3 of immediate operand is one; unchanged otherwise."),
+    z: Flag::Set("Z — Changed if bit 2 of immediate operand is one; unchanged otherwise."),
+    v: Flag::Set("V — Changed if bit 1 of immediate operand is one; unchanged otherwise."),
+    c: Flag::Set("C — Changed if bit 0 of immediate operand is one; unchanged otherwise."),
+};
+
+const FLAGS_SOURCE: FlagsDesc = FlagsDesc {
+    x: Flag::Set("X — Set to the value of bit 4 of the source operand."),
+    n: Flag::Set("N — Set to the value of bit 3 of the source operand."),
+    z: Flag::Set("Z — Set to the value of bit 2 of the source operand."),
+    v: Flag::Set("V — Set to the value of bit 1 of the source operand."),
+    c: Flag::Set("C — Set to the value of bit 0 of the source operand."),
+};
+
+const FLAGS_RTR: FlagsDesc = FlagsDesc {
+    x: Flag::Set("X — Set to the value of bit 4 of the word pulled from the stack."),
+    n: Flag::Set("N — Set to the value of bit 3 of the word pulled from the stack."),
+    z: Flag::Set("Z — Set to the value of bit 2 of the word pulled from the stack."),
+    v: Flag::Set("V — Set to the value of bit 1 of the word pulled from the stack."),
+    c: Flag::Set("C — Set to the value of bit 0 of the word pulled from the stack."),
+};
+
+const FLAGS_CHK2: FlagsDesc = FlagsDesc {
+    x: Flag::NotAffected("X — Not Affected."),
+    n: Flag::Undefined,
+    z: Flag::Set("Z — Set if Rn is equal to either bound; cleared otherwise."),
+    v: Flag::Undefined,
+    c: Flag::Set("C — Set if Rn is out of bounds; cleared otherwise."),
+};
+
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Size {
+    Byte,
+    Word,
+    Long,
+}
 
+impl Size {
+    pub fn suffix(&self) -> &'static str {
+        match *self {
+            Size::Byte => ".b",
+            Size::Word => ".w",
+            Size::Long => ".l",
+        }
+    }
+}
+
+#[derive(PartialEq)]
 pub struct Description {
     pub description: &'static str,
     pub operation: &'static str,
@@ -184,6 +267,20 @@ pub struct Description {
     pub flags: &'static FlagsDesc,
 }
 
+impl Description {
+    // Operation sizes listed in the attributes. Empty for unsized instructions
+    pub fn sizes(&self) -> Vec<Size> {
+        self.attributes.split(',').filter_map(|size| {
+            match size.trim() {
+                "Byte" => Some(Size::Byte),
+                "Word" => Some(Size::Word),
+                "Long" => Some(Size::Long),
+                _ => None,
+            }
+        }).collect()
+    }
+}
+
 pub const ABCD_DESC: Description = Description {
     description: "Adds the source operand to the destination operand along with the extend bit, and stores the result in the destination location. The addition is performed using binary- coded decimal arithmetic. The operands, which are packed binary-coded decimal numbers, can be addressed in two different ways
 
@@ -193,7 +290,7 @@ pub const ABCD_DESC: Description = Description {
 This operation is a byte operation only.",
     operation: "Source10 + Destination10 + X → Destination",
     assembler: &["abcd < ea > ,Dn", "Add Dn, < ea >"],
-    attributes: "Byte, Word, Long",
+    attributes: "Byte",
     flags: &FLAGS_ABCD,
 };
 
@@ -205,6 +302,22 @@ pub const ADD_DESC: Description = Description {
     flags: &FLAGS_ARC,
 };
 
+pub const ADDA_DESC: Description = Description {
+    description: "Adds the source operand to the destination address register and stores the result in the address register. The size of the operation may be specified as word or long. The entire destination address register is used regardless of the operation size.",
+    operation: "Source + Destination → Destination",
+    assembler: &["adda < ea > ,An"],
+    attributes: "Word, Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const ADDI_DESC: Description = Description {
+    description: "Adds the immediate data to the destination operand and stores the result in the destination location. The size of the operation may be specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Immediate Data + Destination → Destination",
+    assembler: &["addi # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_ARC,
+};
+
 pub const ADDQ_DESC: Description = Description {
     description: "Adds an immediate value of one to eight to the operand at the destination location. The size of the operation may be specified as byte, word, or long. Word and long operations are also allowed on the address registers. When adding to address registers, the condition codes are not altered, and the entire destination address register is used regardless of the operation size.",
     operation: "Immidate + Destination → Destination",
@@ -234,6 +347,33 @@ pub const AND_DESC: Description = Description {
     flags: &FLAGS_AND,
 };
 
+pub const ANDI_DESC: Description = Description {
+    description: "Performs an AND operation of the immediate data with the destination operand and stores the result in the destination location. The size of the operation can be specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Immediate Data & Destination → Destination",
+    assembler: &["andi # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_AND,
+};
+
+pub const ANDI_CCR_DESC: Description = Description {
+    description: "Performs an AND operation of the immediate operand with the condition codes and stores the result in the low-order byte of the status register.",
+    operation: "Source & CCR → CCR",
+    assembler: &["andi # < data > ,CCR"],
+    attributes: "Byte",
+    flags: &FLAGS_ANDI_CCR,
+};
+
+pub const ANDI_SR_DESC: Description = Description {
+    description: "Performs an AND operation of the immediate operand with the contents of the status register and stores the result in the status register. All implemented bits of the status register are affected.",
+    operation: "\n
+    If Supervisor State
+        Then Source & SR → SR
+    Else TRAP",
+    assembler: &["andi # < data > ,SR"],
+    attributes: "Word",
+    flags: &FLAGS_ANDI_CCR,
+};
+
 pub const ASL_ASR_DESC: Description = Description {
     description: "Arithmetically shifts the bits of the operand in the direction (L or R) specified. The carry bit receives the last bit shifted out of the operand. The shift count for the shifting of a register may be specified in two different ways:
 
@@ -286,11 +426,19 @@ pub const BCLR_DESC: Description = Description {
     flags: &FLAGS_BINST,
 };
 
+pub const BKPT_DESC: Description = Description {
+    description: "Executes a breakpoint acknowledge bus cycle with the immediate data (value 0 – 7) on bits 2 – 4 of the address bus. When the external hardware doesn't respond to the cycle the processor takes an illegal instruction exception. This instruction is available on the 68010 and later.",
+    operation: "Run Breakpoint Acknowledge Cycle; TRAP As Illegal Instruction",
+    assembler: &["bkpt # < data >"],
+    attributes: "Unsized",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
 pub const BRA_DESC: Description = Description {
     description: "Program execution continues at location (PC) + displacement. The program counter contains the address of the instruction word of the BRA instruction plus two. The displacement is a twos complement integer that represents the relative distance in bytes from the current program counter to the destination program counter. If the 8-bit displacement field in the instruction word is zero, a 16-bit displacement (the word immediately following the instruction) is used. If the 8-bit displacement field in the instruction word is all ones ($FF), the 32-bit displacement (long word immediately following the instruction) is used.",
     operation: "PC + dn → PC",
     assembler: &["bra < label >"],
-    attributes: "Byte, Long",
+    attributes: "Byte, Word",
     flags: &FLAGS_NOT_AFFECTED,
 };
 
@@ -324,6 +472,30 @@ pub const BTST_DESC: Description = Description {
     flags: &FLAGS_BINST,
 };
 
+pub const CHK_DESC: Description = Description {
+    description: "Compares the value in the data register specified in the instruction to zero and to the upper bound (effective address operand). The upper bound is a twos complement integer. If the register value is less than zero or greater than the upper bound, a CHK instruction exception (vector number 6) occurs.",
+    operation: "If Dn < 0 or Dn > Source Then TRAP",
+    assembler: &["chk < ea > ,Dn"],
+    attributes: "Word",
+    flags: &FLAGS_CHK,
+};
+
+pub const CHK_L_DESC: Description = Description {
+    description: "Compares the value in the data register specified in the instruction to zero and to the upper bound (effective address operand). The upper bound is a twos complement integer. If the register value is less than zero or greater than the upper bound, a CHK instruction exception (vector number 6) occurs. The long form is available on the 68020 and later.",
+    operation: "If Dn < 0 or Dn > Source Then TRAP",
+    assembler: &["chk.l < ea > ,Dn"],
+    attributes: "Long",
+    flags: &FLAGS_CHK,
+};
+
+pub const CHK2_DESC: Description = Description {
+    description: "Compares the value in Rn to each bound. The effective address contains the bounds pair: the lower bound followed by the upper bound. For signed comparisons, the arithmetically smaller value should be used as the lower bound. For unsigned comparisons, the logically smaller value should be the lower bound. If Rn is less than the lower bound or greater than the upper bound, a CHK instruction exception (vector number 6) occurs. This instruction is available on the 68020 and later.",
+    operation: "If Rn < LB or Rn > UB Then TRAP",
+    assembler: &["chk2 < ea > ,Rn"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_CHK2,
+};
+
 pub const CLR_DESC: Description = Description {
     description: "Clears the destination operand to zero. The size of the operation may be specified as byte, word, or long.",
     operation: " 0 → Destination",
@@ -340,6 +512,30 @@ pub const CMP_DESC: Description = Description {
     flags: &FLAGS_CMP,
 };
 
+pub const CMP2_DESC: Description = Description {
+    description: "Compares the value in Rn to each bound. The effective address contains the bounds pair: upper bound following the lower bound. For signed comparisons, the arithmetically smaller value should be used as the lower bound. For unsigned comparisons, the logically smaller value should be the lower bound. The condition codes are set according to the result; no exception is taken. This instruction is available on the 68020 and later.",
+    operation: "Compare Rn < LB or Rn > UB and Set Condition Codes",
+    assembler: &["cmp2 < ea > ,Rn"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_CHK2,
+};
+
+pub const CMPA_DESC: Description = Description {
+    description: "Subtracts the source operand from the destination address register and sets the condition codes according to the result; the address register is not changed. The size of the operation can be specified as word or long. Word length source operands are sign-extended to 32 bits for comparison.",
+    operation: "Destination – Source → cc",
+    assembler: &["cmpa < ea > , An"],
+    attributes: "Word, Long",
+    flags: &FLAGS_CMP,
+};
+
+pub const CMPI_DESC: Description = Description {
+    description: "Subtracts the immediate data from the destination operand and sets the condition codes according to the result; the destination location is not changed. The size of the operation may be specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Destination – Immediate Data → cc",
+    assembler: &["cmpi # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_CMP,
+};
+
 pub const CMPM_DESC: Description = Description {
     description: "Subtracts the source operand from the destination operand and sets the condition codes according to the results; the destination location is not changed. The operands are always addressed with the postincrement addressing mode, using the address registers specified in the instruction. The size of the operation may be specified as byte, word, or long.",
     operation: "Destination – Source → cc",
@@ -369,6 +565,14 @@ pub const DIVS_DIVU_DESC: Description = Description {
     flags: &FLAGS_DIV,
 };
 
+pub const DIVS_DIVU_L_DESC: Description = Description {
+    description: "Divides the destination operand by the source operand and stores the result in the destination. The long form divides a long word by a long word, yielding a 32-bit quotient, or divides a quad word held in Dr:Dq by a long word, yielding a 32-bit quotient and a 32-bit remainder. Division by zero causes a trap; on overflow the overflow condition code is set and the operands are unaffected. This form is available on the 68020 and later.",
+    operation: "Destination / Source → Destination",
+    assembler: &["divs.l < ea > ,Dq - 32/32 → 32q", "divs.l < ea > ,Dr:Dq - 64/32 → 32r – 32q", "divsl.l < ea > ,Dr:Dq - 32/32 → 32r – 32q"],
+    attributes: "Long",
+    flags: &FLAGS_DIV,
+};
+
 pub const EOR_DESC: Description = Description {
     description: "Performs an exclusive-OR operation on the destination operand using the source operand and stores the result in the destination location. The size of the operation may be specified to be byte, word, or long. The source operand must be a data register. The destination operand is specified in the effective address field.",
     operation: "Destination EOR Source → Destination",
@@ -377,6 +581,33 @@ pub const EOR_DESC: Description = Description {
     flags: &FLAGS_AND,
 };
 
+pub const EORI_DESC: Description = Description {
+    description: "Performs an exclusive-OR operation on the destination operand using the immediate data and the destination operand and stores the result in the destination location. The size of the operation may be specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Immediate Data EOR Destination → Destination",
+    assembler: &["eori # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_AND,
+};
+
+pub const EORI_CCR_DESC: Description = Description {
+    description: "Performs an exclusive-OR operation on the condition code register using the immediate operand and stores the result in the condition code register (low-order byte of the status register). All implemented bits of the condition code register are affected.",
+    operation: "Source EOR CCR → CCR",
+    assembler: &["eori # < data > ,CCR"],
+    attributes: "Byte",
+    flags: &FLAGS_EORI_CCR,
+};
+
+pub const EORI_SR_DESC: Description = Description {
+    description: "Performs an exclusive-OR operation on the contents of the status register using the immediate operand and stores the result in the status register. All implemented bits of the status register are affected.",
+    operation: "\n
+    If Supervisor State
+        Then Source EOR SR → SR
+    Else TRAP",
+    assembler: &["eori # < data > ,SR"],
+    attributes: "Word",
+    flags: &FLAGS_EORI_CCR,
+};
+
 pub const EXG_DESC: Description = Description {
     description: "Exchanges the contents of two 32-bit registers. The instruction performs three types of exchanges.
 
@@ -397,6 +628,14 @@ pub const EXT_DESC: Description = Description {
     flags: &FLAGS_EXT,
 };
 
+pub const EXTB_DESC: Description = Description {
+    description: "Extends a byte in the specified data register to a long word by copying bit 7 to bits 31 – 8. This instruction is available on the 68020 and later.",
+    operation: "Destination Sign-Extended → Destination",
+    assembler: &["extb.l Dn - extend byte to long"],
+    attributes: "Long",
+    flags: &FLAGS_EXT,
+};
+
 pub const JMP_DESC: Description = Description {
     description: "Program execution continues at the effective address specified by the instruction. The addressing mode for the effective address must be a control addressing mode.",
     operation: "Destination Address → PC",
@@ -458,6 +697,71 @@ pub const MOVE_DESC: Description = Description {
     flags: &FLAGS_EXT,
 };
 
+pub const MOVE_FROM_CCR_DESC: Description = Description {
+    description: "Moves the condition code bits (zero-extended to word size) to the destination location. The operand size is a word. Unimplemented bits are read as zeros. This instruction is available on the 68010 and later.",
+    operation: "CCR → Destination",
+    assembler: &["move CCR, < ea >"],
+    attributes: "Word",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const MOVE_FROM_SR_DESC: Description = Description {
+    description: "Moves the data in the status register to the destination location. The destination is word length. Unimplemented bits are read as zeros.",
+    operation: "SR → Destination",
+    assembler: &["move SR, < ea >"],
+    attributes: "Word",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const MOVE_TO_CCR_DESC: Description = Description {
+    description: "Moves the low-order byte of the source operand to the condition code register. The upper byte of the source operand is ignored; the upper byte of the status register is not altered.",
+    operation: "Source → CCR",
+    assembler: &["move < ea > ,CCR"],
+    attributes: "Word",
+    flags: &FLAGS_SOURCE,
+};
+
+pub const MOVE_TO_SR_DESC: Description = Description {
+    description: "Moves the data in the source operand to the status register. The source operand is a word, and all implemented bits of the status register are affected.",
+    operation: "\n
+    If Supervisor State
+        Then Source → SR
+    Else TRAP",
+    assembler: &["move < ea > ,SR"],
+    attributes: "Word",
+    flags: &FLAGS_SOURCE,
+};
+
+pub const MOVE_USP_DESC: Description = Description {
+    description: "Moves the contents of the user stack pointer to or from the specified address register.",
+    operation: "\n
+    If Supervisor State
+        Then USP → An or An → USP
+    Else TRAP",
+    assembler: &["move USP,An", "move An,USP"],
+    attributes: "Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const MOVEA_DESC: Description = Description {
+    description: "Moves the contents of the source to the destination address register. The size of the operation is specified as word or long. Word-size source operands are sign-extended to 32-bit quantities.",
+    operation: "Source → Destination",
+    assembler: &["movea < ea > ,An"],
+    attributes: "Word, Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const MOVEC_DESC: Description = Description {
+    description: "Moves the contents of the specified control register (Rc) to the specified general register (Rn) or copies the contents of the specified general register to the specified control register. This is always a 32-bit transfer, even though the control register may be implemented with fewer bits. Unimplemented bits are read as zeros. The 68010 implements SFC, DFC, USP and VBR; the 68020 adds CACR, CAAR, MSP and ISP.",
+    operation: "\n
+    If Supervisor State
+        Then Rc → Rn or Rn → Rc
+    Else TRAP",
+    assembler: &["movec Rc,Rn", "movec Rn,Rc"],
+    attributes: "Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
 pub const MOVEM_DESC: Description = Description {
     description: "Moves the contents of selected registers to or from consecutive memory locations starting at the location specified by the effective address. A register is selected if the bit in the mask field corresponding to that register is set. The instruction size determines whether 16 or 32 bits of each register are transferred. In the case of a word transfer to either address or data registers, each word is sign-extended to 32 bits, and the resulting long word is loaded into the associated register.
 Selecting the addressing mode also selects the mode of operation of the MOVEM instruction, and only the control modes, the predecrement mode, and the postincre- ment mode are valid. If the effective address is specified by one of the control modes, the registers are transferred starting at the specified address, and the address is incre- mented by the operand length (2 or 4) following each transfer. The order of the regis- ters is from D0 to D7, then from A0 to A7.
@@ -465,7 +769,7 @@ If the effective address is specified by the predecrement mode, only a register-
 If the effective address is specified by the postincrement mode, only a memory-to-reg- ister operation is allowed. The registers are loaded starting at the specified address; the address is incremented by the operand length (2 or 4) following each transfer. The order of loading is the same as that of control mode addressing. When the instruction has completed, the incremented address register contains the address of the last oper- and loaded plus the operand length. If the addressing register is also loaded from memory, the memory value is ignored and the register is written with the postincre- mented effective address.",
     operation: "Registers → Destination; Source → Registers",
     assembler: &["movem < list >, < ea >", "movem < list >, < ea >"],
-    attributes: "Byte, Word, Long",
+    attributes: "Word, Long",
     flags: &FLAGS_NOT_AFFECTED,
 };
 
@@ -486,10 +790,29 @@ pub const MOVEQ_DESC: Description = Description {
     flags: &FLAGS_EXT,
 };
 
+pub const MOVES_DESC: Description = Description {
+    description: "Moves the byte, word, or long operand from the specified general register to a location within the address space specified by the destination function code (DFC) register, or moves the operand from a location within the address space specified by the source function code (SFC) register to the specified general register. This instruction is available on the 68010 and later.",
+    operation: "\n
+    If Supervisor State
+        Then Rn → Destination Using DFC or Source Using SFC → Rn
+    Else TRAP",
+    assembler: &["moves Rn, < ea >", "moves < ea > ,Rn"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
 pub const MULS_DESC: Description = Description {
     description: "Multiplies two signed operands yielding a signed result. The multiplier and multiplicand are both word operands, and the result is a long-word operand. A register operand is the low-order word; the upper word of the register is ignored. All 32 bits of the product are saved in the destination data register.",
     operation: "Source * Destination → Destination",
     assembler: &["muls.w < ea > ,Dn - 16 x 16 → 32"],
+    attributes: "Word",
+    flags: &FLAGS_MUL,
+};
+
+pub const MULS_L_DESC: Description = Description {
+    description: "Multiplies two signed operands yielding a signed result. In the long form the multiplier and multiplicand are both long words and the result is either a long word or a quad word. The long word result (the low-order 32 bits) is stored in the destination data register Dl, or the quad word result is split between the destination data register Dl and another data register Dh. This form is available on the 68020 and later.",
+    operation: "Source * Destination → Destination",
+    assembler: &["muls.l < ea > ,Dl - 32 x 32 → 32", "muls.l < ea > ,Dh:Dl - 32 x 32 → 64"],
     attributes: "Long",
     flags: &FLAGS_MUL,
 };
@@ -498,10 +821,26 @@ pub const MULU_DESC: Description = Description {
     description: "Multiplies two signed operands yielding a unsigned result. The multiplier and multiplicand are both word operands, and the result is a long-word operand. A register operand is the low-order word; the upper word of the register is ignored. All 32 bits of the product are saved in the destination data register.",
     operation: "Source * Destination → Destination",
     assembler: &["muls.w < ea > ,Dn - 16 x 16 → 32"],
+    attributes: "Word",
+    flags: &FLAGS_MUL,
+};
+
+pub const MULU_L_DESC: Description = Description {
+    description: "Multiplies two unsigned operands yielding an unsigned result. In the long form the multiplier and multiplicand are both long words and the result is either a long word or a quad word. The long word result (the low-order 32 bits) is stored in the destination data register Dl, or the quad word result is split between the destination data register Dl and another data register Dh. This form is available on the 68020 and later.",
+    operation: "Source * Destination → Destination",
+    assembler: &["mulu.l < ea > ,Dl - 32 x 32 → 32", "mulu.l < ea > ,Dh:Dl - 32 x 32 → 64"],
     attributes: "Long",
     flags: &FLAGS_MUL,
 };
 
+pub const NBCD_DESC: Description = Description {
+    description: "Subtracts the destination operand and the extend bit from zero. The operation is performed using binary-coded decimal arithmetic. The packed binary-coded decimal result is saved in the destination location. This instruction produces the tens complement of the destination if the extend bit is zero or the nines complement if the extend bit is one. This is a byte operation only.",
+    operation: "0 – Destination10 – X → Destination",
+    assembler: &["nbcd < ea >"],
+    attributes: "Byte",
+    flags: &FLAGS_SBCD,
+};
+
 pub const NEG_DESC: Description = Description {
     description: "Subtracts the destination operand from zero and stores the result in the destination location. The size of the operation is specified as byte, word, or long.",
     operation: "0 – Destination → Destination",
@@ -522,7 +861,7 @@ pub const NOP_DESC: Description = Description {
     description: "Performs no operation. The processor state, other than the program counter, is unaffected. Execution continues with the instruction following the NOP instruction. The NOP instruction does not begin execution until all pending bus cycles have completed. This synchronizes the pipeline and prevents instruction overlap.",
     operation: "None",
     assembler: &["nop"],
-    attributes: "Undefined",
+    attributes: "Unsized",
     flags: &FLAGS_NOT_AFFECTED,
 };
 
@@ -542,6 +881,33 @@ pub const OR_DESC: Description = Description {
     flags: &FLAGS_AND,
 };
 
+pub const ORI_DESC: Description = Description {
+    description: "Performs an inclusive-OR operation on the immediate data and the destination operand and stores the result in the destination location. The size of the operation is specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Immediate Data | Destination → Destination",
+    assembler: &["ori # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_AND,
+};
+
+pub const ORI_CCR_DESC: Description = Description {
+    description: "Performs an inclusive-OR operation on the immediate operand and the condition codes and stores the result in the condition code register (low-order byte of the status register). All implemented bits of the condition code register are affected.",
+    operation: "Source | CCR → CCR",
+    assembler: &["ori # < data > ,CCR"],
+    attributes: "Byte",
+    flags: &FLAGS_ORI_CCR,
+};
+
+pub const ORI_SR_DESC: Description = Description {
+    description: "Performs an inclusive-OR operation of the immediate operand and the contents of the status register and stores the result in the status register. All implemented bits of the status register are affected.",
+    operation: "\n
+    If Supervisor State
+        Then Source | SR → SR
+    Else TRAP",
+    assembler: &["ori # < data > ,SR"],
+    attributes: "Word",
+    flags: &FLAGS_ORI_CCR,
+};
+
 pub const PEA_DESC: Description = Description {
     description: "Computes the effective address and pushes it onto the stack. The effective address is a long address.",
     operation: "SP – 4 → SP; < ea > → (SP)",
@@ -550,6 +916,17 @@ pub const PEA_DESC: Description = Description {
     flags: &FLAGS_NOT_AFFECTED,
 };
 
+pub const RESET_DESC: Description = Description {
+    description: "Asserts the RESET signal for 124 clock periods, resetting all external devices. The processor state, other than the program counter, is unaffected, and execution continues with the next instruction.",
+    operation: "\n
+    If Supervisor State
+        Then Assert RESET Line
+    Else TRAP",
+    assembler: &["reset"],
+    attributes: "Unsized",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
 pub const ROL_ROR_DESC: Description = Description {
     description: "Rotates the bits of the operand in the direction specified (L or R). The extend bit is not included in the rotation. The rotate count for the rotation of a register is specified in either of two ways:
 
@@ -578,6 +955,22 @@ The size of the operation for register destinations is specified as byte, word,
     flags: &FLAGS_ROL,
 };
 
+pub const RTD_DESC: Description = Description {
+    description: "Pulls the program counter value from the stack and adds the sign-extended 16-bit displacement value to the stack pointer. The previous program counter value is lost. This instruction is available on the 68010 and later.",
+    operation: "(SP) → PC; SP + 4 + dn → SP",
+    assembler: &["rtd # < displacement >"],
+    attributes: "Unsized",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const RTR_DESC: Description = Description {
+    description: "Pulls the condition code and program counter values from the stack. The previous condition code and program counter values are lost. The supervisor portion of the status register is unaffected.",
+    operation: "(SP) → CC; SP + 2 → SP; (SP) → PC; SP + 4 → SP",
+    assembler: &["rtr"],
+    attributes: "Unsized",
+    flags: &FLAGS_RTR,
+};
+
 pub const RTS_DESC: Description = Description {
     description: "Pulls the program counter value from the stack. The previous program counter value is lost.",
     operation: "(SP) → PC; SP + 4 → SP",
@@ -597,6 +990,19 @@ pub const RTE_DESC: Description = Description {
     flags: &FLAGS_NOT_AFFECTED,
 };
 
+pub const SBCD_DESC: Description = Description {
+    description: "Subtracts the source operand and the extend bit from the destination operand and stores the result in the destination location. The subtraction is performed using binary-coded decimal arithmetic; the operands are packed binary-coded decimal numbers. The instruction has two modes:
+
+1. Data register to data register—the data registers specified in the instruction contain the operands.
+2. Memory to memory—the address registers specified in the instruction access the operands from memory using the predecrement addressing mode.
+
+This operation is a byte operation only.",
+    operation: "Destination10 – Source10 – X → Destination",
+    assembler: &["sbcd Dy,Dx", "sbcd -(Ay),-(Ax)"],
+    attributes: "Byte",
+    flags: &FLAGS_SBCD,
+};
+
 pub const SCC_DESC: Description = Description {
     description: "Tests the specified condition code; if the condition is true, sets the byte specified by the effective address to TRUE (all ones). Otherwise, sets that byte to FALSE (all zeros).",
     operation:"\n
@@ -608,6 +1014,17 @@ pub const SCC_DESC: Description = Description {
     flags: &FLAGS_NOT_AFFECTED,
 };
 
+pub const STOP_DESC: Description = Description {
+    description: "Moves the immediate operand into the status register (both user and supervisor portions), advances the program counter to point to the next instruction, and stops the fetching and executing of instructions. A trace, interrupt, or reset exception causes the processor to resume instruction execution.",
+    operation: "\n
+    If Supervisor State
+        Then Immediate Data → SR; STOP
+    Else TRAP",
+    assembler: &["stop # < data >"],
+    attributes: "Unsized",
+    flags: &FLAGS_SOURCE,
+};
+
 pub const SUB_DESC: Description = Description {
     description: "Subtracts the source operand from the destination operand and stores the result in the destination. The size of the operation is specified as byte, word, or long. The mode of the instruction indicates which operand is the source, which is the destination, and which is the operand size. Notice that conditon flags are not affected when target is An",
     operation: "Source - Destination → Destination",
@@ -616,6 +1033,22 @@ pub const SUB_DESC: Description = Description {
     flags: &FLAGS_SUB,
 };
 
+pub const SUBA_DESC: Description = Description {
+    description: "Subtracts the source operand from the destination address register and stores the result in the address register. The size of the operation is specified as word or long. Word-sized source operands are sign-extended to 32-bit quantities prior to the subtraction.",
+    operation: "Destination – Source → Destination",
+    assembler: &["suba < ea > ,An"],
+    attributes: "Word, Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const SUBI_DESC: Description = Description {
+    description: "Subtracts the immediate data from the destination operand and stores the result in the destination location. The size of the operation is specified as byte, word, or long. The size of the immediate data matches the operation size.",
+    operation: "Destination – Immediate Data → Destination",
+    assembler: &["subi # < data > , < ea >"],
+    attributes: "Byte, Word, Long",
+    flags: &FLAGS_SUB,
+};
+
 pub const SUBQ_DESC: Description = Description {
     description: "Subtracts the immediate data (1 – 8) from the destination operand. The size of the operation is specified as byte, word, or long. Only word and long operations can be used with address registers, and the condition codes are not affected. When subtracting from address registers, the entire destination address register is used, despite the operation size.",
     operation: "Immidate - Destination → Destination",
@@ -665,6 +1098,22 @@ pub const TRAP_DESC: Description = Description {
     flags: &FLAGS_NOT_AFFECTED,
 };
 
+pub const TRAPCC_DESC: Description = Description {
+    description: "If the specified condition is true, causes a TRAPcc exception with a vector number 7. The processor pushes the address of the next instruction word (currently in the program counter) onto the stack. If the condition is not true, the processor performs no operation, and execution continues with the next instruction. The optional immediate word or long word operand follows the operation word and is available to the trap handler. This instruction is available on the 68020 and later.",
+    operation: "If cc Then TRAP",
+    assembler: &["trapcc"],
+    attributes: "Unsized",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
+pub const TRAPCC_IMM_DESC: Description = Description {
+    description: "If the specified condition is true, causes a TRAPcc exception with a vector number 7. The processor pushes the address of the next instruction word (currently in the program counter) onto the stack. If the condition is not true, the processor performs no operation, and execution continues with the next instruction. The immediate word or long word operand follows the operation word and is available to the trap handler. This instruction is available on the 68020 and later.",
+    operation: "If cc Then TRAP",
+    assembler: &["trapcc.w # < data >", "trapcc.l # < data >"],
+    attributes: "Word, Long",
+    flags: &FLAGS_NOT_AFFECTED,
+};
+
 pub const TRAPV_DESC: Description = Description {
     description: "If the overflow condition is set, causes a TRAPV exception with a vector number 7. If the overflow condition is not set, the processor performs no operation and execution continues with the next instruction.",
     operation: "\n
@@ -691,5 +1140,99 @@ pub const UNLK_DESC: Description = Description {
     flags: &FLAGS_NOT_AFFECTED,
 };
 
-
-
+// Every description above, used to report descriptions that no instruction refers to
+pub const DESCRIPTIONS: &[(&str, &Description)] = &[
+    ("ABCD_DESC", &ABCD_DESC),
+    ("ADD_DESC", &ADD_DESC),
+    ("ADDA_DESC", &ADDA_DESC),
+    ("ADDI_DESC", &ADDI_DESC),
+    ("ADDQ_DESC", &ADDQ_DESC),
+    ("ADDX_DESC", &ADDX_DESC),
+    ("AND_DESC", &AND_DESC),
+    ("ANDI_DESC", &ANDI_DESC),
+    ("ANDI_CCR_DESC", &ANDI_CCR_DESC),
+    ("ANDI_SR_DESC", &ANDI_SR_DESC),
+    ("ASL_ASR_DESC", &ASL_ASR_DESC),
+    ("BCC_DESC", &BCC_DESC),
+    ("BCHG_DESC", &BCHG_DESC),
+    ("BCLR_DESC", &BCLR_DESC),
+    ("BKPT_DESC", &BKPT_DESC),
+    ("BRA_DESC", &BRA_DESC),
+    ("BSET_DESC", &BSET_DESC),
+    ("BSR_DESC", &BSR_DESC),
+    ("BTST_DESC", &BTST_DESC),
+    ("CHK_DESC", &CHK_DESC),
+    ("CHK_L_DESC", &CHK_L_DESC),
+    ("CHK2_DESC", &CHK2_DESC),
+    ("CLR_DESC", &CLR_DESC),
+    ("CMP_DESC", &CMP_DESC),
+    ("CMP2_DESC", &CMP2_DESC),
+    ("CMPA_DESC", &CMPA_DESC),
+    ("CMPI_DESC", &CMPI_DESC),
+    ("CMPM_DESC", &CMPM_DESC),
+    ("DBCC_DESC", &DBCC_DESC),
+    ("DIVS_DIVU_DESC", &DIVS_DIVU_DESC),
+    ("DIVS_DIVU_L_DESC", &DIVS_DIVU_L_DESC),
+    ("EOR_DESC", &EOR_DESC),
+    ("EORI_DESC", &EORI_DESC),
+    ("EORI_CCR_DESC", &EORI_CCR_DESC),
+    ("EORI_SR_DESC", &EORI_SR_DESC),
+    ("EXG_DESC", &EXG_DESC),
+    ("EXT_DESC", &EXT_DESC),
+    ("EXTB_DESC", &EXTB_DESC),
+    ("JMP_DESC", &JMP_DESC),
+    ("JSR_DESC", &JSR_DESC),
+    ("LEA_DESC", &LEA_DESC),
+    ("ILLEGAL_DESC", &ILLEGAL_DESC),
+    ("LINK_DESC", &LINK_DESC),
+    ("LSL_LSR_DESC", &LSL_LSR_DESC),
+    ("MOVE_DESC", &MOVE_DESC),
+    ("MOVE_FROM_CCR_DESC", &MOVE_FROM_CCR_DESC),
+    ("MOVE_FROM_SR_DESC", &MOVE_FROM_SR_DESC),
+    ("MOVE_TO_CCR_DESC", &MOVE_TO_CCR_DESC),
+    ("MOVE_TO_SR_DESC", &MOVE_TO_SR_DESC),
+    ("MOVE_USP_DESC", &MOVE_USP_DESC),
+    ("MOVEA_DESC", &MOVEA_DESC),
+    ("MOVEC_DESC", &MOVEC_DESC),
+    ("MOVEM_DESC", &MOVEM_DESC),
+    ("MOVEP_DESC", &MOVEP_DESC),
+    ("MOVEQ_DESC", &MOVEQ_DESC),
+    ("MOVES_DESC", &MOVES_DESC),
+    ("MULS_DESC", &MULS_DESC),
+    ("MULS_L_DESC", &MULS_L_DESC),
+    ("MULU_DESC", &MULU_DESC),
+    ("MULU_L_DESC", &MULU_L_DESC),
+    ("NBCD_DESC", &NBCD_DESC),
+    ("NEG_DESC", &NEG_DESC),
+    ("NEGX_DESC", &NEGX_DESC),
+    ("NOP_DESC", &NOP_DESC),
+    ("NOT_DESC", &NOT_DESC),
+    ("OR_DESC", &OR_DESC),
+    ("ORI_DESC", &ORI_DESC),
+    ("ORI_CCR_DESC", &ORI_CCR_DESC),
+    ("ORI_SR_DESC", &ORI_SR_DESC),
+    ("PEA_DESC", &PEA_DESC),
+    ("RESET_DESC", &RESET_DESC),
+    ("ROL_ROR_DESC", &ROL_ROR_DESC),
+    ("ROXL_ROXR_DESC", &ROXL_ROXR_DESC),
+    ("RTD_DESC", &RTD_DESC),
+    ("RTR_DESC", &RTR_DESC),
+    ("RTS_DESC", &RTS_DESC),
+    ("RTE_DESC", &RTE_DESC),
+    ("SBCD_DESC", &SBCD_DESC),
+    ("SCC_DESC", &SCC_DESC),
+    ("STOP_DESC", &STOP_DESC),
+    ("SUB_DESC", &SUB_DESC),
+    ("SUBA_DESC", &SUBA_DESC),
+    ("SUBI_DESC", &SUBI_DESC),
+    ("SUBQ_DESC", &SUBQ_DESC),
+    ("SUBX_DESC", &SUBX_DESC),
+    ("SWAP_DESC", &SWAP_DESC),
+    ("TAS_DESC", &TAS_DESC),
+    ("TRAP_DESC", &TRAP_DESC),
+    ("TRAPCC_DESC", &TRAPCC_DESC),
+    ("TRAPCC_IMM_DESC", &TRAPCC_IMM_DESC),
+    ("TRAPV_DESC", &TRAPV_DESC),
+    ("TST_DESC", &TST_DESC),
+    ("UNLK_DESC", &UNLK_DESC),
+];
@@ -0,0 +1,125 @@
+// Report of the statements the assembler rejected and why.
+//
+// Every rejection is recorded once per CPU and statement as it comes out of the
+// assembler, so tables that assemble the same statement again (e.g. one per preset)
+// don't count it twice. Tool failures mean the tables are incomplete, main exits
+// with an error if there were any.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use assembler::{Category, Rejection};
+use cpu::Cpu;
+
+struct Diagnostic {
+    cpu: Cpu,
+    statement: String,
+    rejection: Rejection,
+}
+
+#[derive(Default)]
+pub struct Diagnostics {
+    // In the order they were recorded
+    entries: Vec<Diagnostic>,
+    // CPU and statement of every recorded diagnostic
+    recorded: BTreeSet<(&'static str, String)>,
+}
+
+fn count(diagnostics: &[Diagnostic], cpu: Option<Cpu>, category: Category) -> usize {
+    diagnostics.iter()
+        .filter(|d| d.rejection.category == category && cpu.unwrap_or(d.cpu) == d.cpu)
+        .count()
+}
+
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics::default()
+    }
+
+    pub(crate) fn record(&mut self, cpu: Cpu, statement: &str, rejection: &Rejection) {
+        if self.recorded.insert((cpu.name(), statement.to_owned())) {
+            self.entries.push(Diagnostic {
+                cpu,
+                statement: statement.to_owned(),
+                rejection: rejection.clone(),
+            });
+        }
+    }
+
+    // Rejected statements per category, None if nothing was rejected
+    pub fn summary(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let counts: Vec<String> = Category::all().iter()
+            .map(|&category| format!("{} {}", count(&self.entries, None, category), category.name()))
+            .collect();
+
+        Some(format!("rejected statements: {}", counts.join(", ")))
+    }
+
+    pub fn has_tool_failures(&self) -> bool {
+        count(&self.entries, None, Category::ToolFailure) > 0
+    }
+
+    // Markdown report with the counts per CPU and every rejected statement with the
+    // assembler's message, grouped by CPU and category
+    pub fn write_report<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let diagnostics = &self.entries;
+        let mut cpus: Vec<Cpu> = Vec::new();
+
+        for d in diagnostics.iter() {
+            if !cpus.contains(&d.cpu) {
+                cpus.push(d.cpu);
+            }
+        }
+
+        writeln!(w, "# Assembler Diagnostics\n")?;
+
+        write!(w, "| CPU ")?;
+
+        for category in Category::all() {
+            write!(w, "| {} ", category.name())?;
+        }
+
+        writeln!(w, "|")?;
+        writeln!(w, "|-----{}|", "|-----".repeat(Category::all().len()))?;
+
+        for &cpu in &cpus {
+            write!(w, "| {} ", cpu.name())?;
+
+            for &category in Category::all() {
+                write!(w, "| {} ", count(diagnostics, Some(cpu), category))?;
+            }
+
+            writeln!(w, "|")?;
+        }
+
+        for &cpu in &cpus {
+            for &category in Category::all() {
+                let entries: Vec<&Diagnostic> = diagnostics.iter()
+                    .filter(|d| d.cpu == cpu && d.rejection.category == category)
+                    .collect();
+
+                if entries.is_empty() {
+                    continue;
+                }
+
+                writeln!(w, "\n## MC{}: {}\n", cpu.name(), category.name())?;
+                writeln!(w, "| Statement | Message |")?;
+                writeln!(w, "|-----------|---------|")?;
+
+                for d in entries {
+                    writeln!(w, "| `{}` | {} |", d.statement, cell(&d.rejection.message))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
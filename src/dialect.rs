@@ -0,0 +1,336 @@
+// Translation of the generator's statements into the operand syntax of GNU as.
+//
+// Statements are written in the Motorola syntax vasm takes: $ hex numbers, *+n for the
+// current location, 2(a0,d0) and $800.W. GNU as wants % register prefixes, 0x numbers and
+// . for the location, and takes either its own flavour of Motorola syntax, (2,%a0,%d0) and
+// (0x800).w, or MIT syntax, %a0@(2,%d0:w) and 0x800:w.
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Syntax {
+    Motorola,
+    Mit,
+}
+
+const REGISTERS: &[&str] = &[
+    "d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7",
+    "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7",
+    "sp", "pc", "zpc", "ccr", "sr", "usp",
+    "sfc", "dfc", "vbr", "cacr", "caar", "msp", "isp",
+];
+
+fn is_register(text: &str) -> bool {
+    REGISTERS.contains(&text)
+}
+
+// Splits at commas outside of parentheses and brackets
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    if !text.is_empty() {
+        parts.push(&text[start..]);
+    }
+
+    parts
+}
+
+// $ hex to 0x hex, e.g. -$7f8 to -0x7f8
+fn number(text: &str) -> String {
+    text.replace('$', "0x")
+}
+
+// Number with an optional .w/.l size, e.g. $ff.w
+fn sized_number(text: &str, syntax: Syntax) -> String {
+    let lower = text.to_lowercase();
+
+    for &(suffix, mit) in &[(".w", ":w"), (".l", ":l")] {
+        if lower.ends_with(suffix) {
+            let value = number(&text[..text.len() - 2]);
+
+            return match syntax {
+                Syntax::Motorola => format!("{}{}", value, suffix),
+                Syntax::Mit => format!("{}{}", value, mit),
+            };
+        }
+    }
+
+    number(text)
+}
+
+// Index register with optional size and scale, e.g. d0.l*4
+fn index(text: &str, syntax: Syntax) -> String {
+    let lower = text.to_lowercase();
+    let mut scale_parts = lower.splitn(2, '*');
+    let mut size_parts = scale_parts.next().unwrap().splitn(2, '.');
+    let reg = size_parts.next().unwrap();
+
+    match syntax {
+        Syntax::Motorola => format!("%{}", lower),
+        Syntax::Mit => {
+            let mut text = format!("%{}", reg);
+
+            if let Some(size) = size_parts.next() {
+                text.push_str(&format!(":{}", size));
+            }
+
+            if let Some(scale) = scale_parts.next() {
+                text.push_str(&format!(":{}", scale));
+            }
+
+            text
+        }
+    }
+}
+
+// Register, index register or displacement inside parentheses
+fn element(text: &str, syntax: Syntax) -> String {
+    let text = text.trim();
+    let lower = text.to_lowercase();
+    let reg = lower.split(['.', '*']).next().unwrap();
+
+    if is_register(reg) {
+        if reg == lower { format!("%{}", lower) } else { index(text, syntax) }
+    } else {
+        sized_number(text, syntax)
+    }
+}
+
+fn is_base(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    lower.starts_with('a') || lower == "sp" || lower == "pc" || lower == "zpc"
+}
+
+// MIT form of a list of base, displacement and index, e.g. %a0@(2,%d0:w)
+fn mit_address(elements: &[&str]) -> String {
+    let base = elements.iter().find(|e| is_base(e) && is_register(&e.trim().to_lowercase()));
+
+    let rest: Vec<String> = elements.iter()
+        .filter(|e| Some(*e) != base)
+        .map(|e| element(e, Syntax::Mit))
+        .collect();
+
+    let base = base.map(|b| element(b, Syntax::Mit)).unwrap_or("%zpc".to_owned());
+
+    match rest.is_empty() {
+        true => format!("{}@", base),
+        false => format!("{}@({})", base, rest.join(",")),
+    }
+}
+
+// ([bd,An],Xn,od) and ([bd,An,Xn],od)
+fn memory_indirect(text: &str, syntax: Syntax) -> String {
+    let close = text.find(']').unwrap_or(text.len() - 1);
+    let inner = split_top_level(&text[2..close]);
+    let outer: Vec<&str> = split_top_level(&text[close + 1..text.len() - 1])
+        .into_iter()
+        .filter(|part| !part.trim().is_empty())
+        .collect();
+
+    match syntax {
+        Syntax::Motorola => {
+            let inner: Vec<String> = inner.iter().map(|e| element(e, syntax)).collect();
+            let mut text = format!("([{}]", inner.join(","));
+
+            for part in outer {
+                text.push_str(&format!(",{}", element(part, syntax)));
+            }
+
+            text + ")"
+        }
+        Syntax::Mit => {
+            let outer: Vec<String> = outer.iter().map(|e| element(e, syntax)).collect();
+            // The outer displacement goes first, e.g. %a0@(-0x7f8)@(0x3ff,%d0:l)
+            let (mut od, index): (Vec<String>, Vec<String>) = outer.into_iter().partition(|e| !e.starts_with('%'));
+            od.extend(index);
+
+            // A base without displacement still needs its own parentheses, %a0@(0)@(0x3ff)
+            let inner = match mit_address(&inner) {
+                ref base if base.ends_with('@') => format!("{}(0)", base),
+                inner => inner,
+            };
+
+            format!("{}@({})", inner, od.join(",")).replace("@()", "@(0)")
+        }
+    }
+}
+
+fn register_list(text: &str) -> String {
+    text.split('/')
+        .map(|range| range.split('-').map(|reg| format!("%{}", reg.to_lowercase())).collect::<Vec<_>>().join("-"))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_register_list(text: &str) -> bool {
+    text.split(['/', '-']).all(|reg| is_register(&reg.to_lowercase()))
+}
+
+fn operand(text: &str, syntax: Syntax) -> String {
+    let text = text.trim();
+
+    if let Some(value) = text.strip_prefix('#') {
+        return format!("#{}", number(value));
+    }
+
+    if let Some(offset) = text.strip_prefix('*') {
+        return format!(".{}", number(offset));
+    }
+
+    if is_register_list(text) {
+        return register_list(text);
+    }
+
+    if text.starts_with("-(") && text.ends_with(')') {
+        let reg = element(&text[2..text.len() - 1], syntax);
+
+        return match syntax {
+            Syntax::Motorola => format!("-({})", reg),
+            Syntax::Mit => format!("{}@-", reg),
+        };
+    }
+
+    if text.starts_with('(') && text.ends_with(")+") {
+        let reg = element(&text[1..text.len() - 2], syntax);
+
+        return match syntax {
+            Syntax::Motorola => format!("({})+", reg),
+            Syntax::Mit => format!("{}@+", reg),
+        };
+    }
+
+    if text.starts_with("([") {
+        return memory_indirect(text, syntax);
+    }
+
+    // (a0), (d8,a0,d0.l*4) and 2(a0,d0) all become a list of elements
+    if text.ends_with(')') {
+        if let Some(open) = text.find('(') {
+            let mut elements = split_top_level(&text[open + 1..text.len() - 1]);
+
+            if open > 0 {
+                elements.insert(0, &text[..open]);
+            }
+
+            return match syntax {
+                Syntax::Motorola => {
+                    let elements: Vec<String> = elements.iter().map(|e| element(e, syntax)).collect();
+                    format!("({})", elements.join(","))
+                }
+                Syntax::Mit => mit_address(&elements),
+            };
+        }
+    }
+
+    // Absolute address, e.g. $800.W
+    let lower = text.to_lowercase();
+
+    match syntax {
+        Syntax::Motorola if lower.ends_with(".w") || lower.ends_with(".l") => {
+            format!("({}){}", number(&text[..text.len() - 2]), &lower[lower.len() - 2..])
+        }
+        _ => sized_number(text, syntax),
+    }
+}
+
+// The statement in GNU as syntax, e.g. "add.w 2(a0,d0),d1" to "add.w (2,%a0,%d0),%d1"
+pub fn translate(statement: &str, syntax: Syntax) -> String {
+    let statement = statement.trim();
+
+    let (mnemonic, operands) = match statement.find(char::is_whitespace) {
+        Some(i) => (&statement[..i], statement[i..].trim()),
+        None => (statement, ""),
+    };
+
+    let mnemonic = match mnemonic.to_lowercase().as_str() {
+        "dc.w" => ".word".to_owned(),
+        _ => mnemonic.to_owned(),
+    };
+
+    let operands: Vec<String> = split_top_level(operands).iter().map(|op| operand(op, syntax)).collect();
+
+    match operands.is_empty() {
+        true => mnemonic,
+        false => format!("{} {}", mnemonic, operands.join(",")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(statement: &str, motorola: &str, mit: &str) {
+        assert_eq!(translate(statement, Syntax::Motorola), motorola);
+        assert_eq!(translate(statement, Syntax::Mit), mit);
+    }
+
+    #[test]
+    fn registers_and_immediates() {
+        check("nop", "nop", "nop");
+        check("move.w d0,d1", "move.w %d0,%d1", "move.w %d0,%d1");
+        check("move.l #$12345678,a7", "move.l #0x12345678,%a7", "move.l #0x12345678,%a7");
+        check("move.w d0,sr", "move.w %d0,%sr", "move.w %d0,%sr");
+        check("move.l usp,a0", "move.l %usp,%a0", "move.l %usp,%a0");
+        check("dc.w $4e71", ".word 0x4e71", ".word 0x4e71");
+    }
+
+    #[test]
+    fn control_registers() {
+        check("movec vbr,d0", "movec %vbr,%d0", "movec %vbr,%d0");
+        check("movec d0,cacr", "movec %d0,%cacr", "movec %d0,%cacr");
+        check("movec a0,sfc", "movec %a0,%sfc", "movec %a0,%sfc");
+        check("movec isp,a1", "movec %isp,%a1", "movec %isp,%a1");
+    }
+
+    #[test]
+    fn register_indirect() {
+        check("move.w (a0),d1", "move.w (%a0),%d1", "move.w %a0@,%d1");
+        check("move.w (a0)+,-(a1)", "move.w (%a0)+,-(%a1)", "move.w %a0@+,%a1@-");
+        check("move.w 2(a0),d1", "move.w (2,%a0),%d1", "move.w %a0@(2),%d1");
+        check("add.w 2(a0,d0),d1", "add.w (2,%a0,%d0),%d1", "add.w %a0@(2,%d0),%d1");
+        check("move.w -2(a0,d0.l),d1", "move.w (-2,%a0,%d0.l),%d1", "move.w %a0@(-2,%d0:l),%d1");
+        check("lea 2(pc),a0", "lea (2,%pc),%a0", "lea %pc@(2),%a0");
+        check("lea 2(pc,d0.w),a0", "lea (2,%pc,%d0.w),%a0", "lea %pc@(2,%d0:w),%a0");
+    }
+
+    #[test]
+    fn absolute_and_relative() {
+        check("move.w $800.W,d0", "move.w (0x800).w,%d0", "move.w 0x800:w,%d0");
+        check("move.w $12345678.l,d0", "move.w (0x12345678).l,%d0", "move.w 0x12345678:l,%d0");
+        check("bra *+10", "bra .+10", "bra .+10");
+        check("dbf d0,*+10", "dbf %d0,.+10", "dbf %d0,.+10");
+    }
+
+    #[test]
+    fn register_lists() {
+        check("movem.l d0-d7/a0-a6,-(sp)", "movem.l %d0-%d7/%a0-%a6,-(%sp)", "movem.l %d0-%d7/%a0-%a6,%sp@-");
+        check("movem.w (a0)+,d0/a1", "movem.w (%a0)+,%d0/%a1", "movem.w %a0@+,%d0/%a1");
+    }
+
+    #[test]
+    fn scaled_index_and_memory_indirect() {
+        check("move.w (8,a0,d0.l*4),d1", "move.w (8,%a0,%d0.l*4),%d1", "move.w %a0@(8,%d0:l:4),%d1");
+        check("move.w ([-$7f8,a0],d0.l,$3ff),d1",
+              "move.w ([-0x7f8,%a0],%d0.l,0x3ff),%d1",
+              "move.w %a0@(-0x7f8)@(0x3ff,%d0:l),%d1");
+        check("move.w ([-$7f9,a0,d0.l],$400),d1",
+              "move.w ([-0x7f9,%a0,%d0.l],0x400),%d1",
+              "move.w %a0@(-0x7f9,%d0:l)@(0x400),%d1");
+        check("move.w ([8,zpc],d0.l,$3ff),d1",
+              "move.w ([8,%zpc],%d0.l,0x3ff),%d1",
+              "move.w %zpc@(8)@(0x3ff,%d0:l),%d1");
+        check("move.w ([a0]),d1", "move.w ([%a0]),%d1", "move.w %a0@(0)@(0),%d1");
+    }
+}
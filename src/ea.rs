@@ -0,0 +1,243 @@
+// Decomposition of the measured timings into a base time plus the effective address
+// calculation time of the source operand, the way Motorola documents the 68000.
+//
+// The EA time of a mode is the difference to the Dn source most often seen across
+// all grid tables, separately for byte/word and long operations. The base time of a
+// destination column is then the most common measurement minus the EA time of its
+// source. Cells that don't equal base + EA are anomalies.
+
+use std::collections::HashMap;
+
+use cpu::Cpu;
+use descriptions::Size;
+use {BuildResult, Chapter, Instruction, Op, Table, TableLayout, TimingTable};
+
+const MODES: &[Op] = &[
+    Op { name: "", print_name: "Dn" },
+    Op { name: "", print_name: "An" },
+    Op { name: "", print_name: "(An)" },
+    Op { name: "", print_name: "(An)+" },
+    Op { name: "", print_name: "-(An)" },
+    Op { name: "", print_name: "d(An)" },
+    Op { name: "", print_name: "d(An,Dn)" },
+    Op { name: "", print_name: "xxx.W" },
+    Op { name: "", print_name: "xxx.L" },
+    Op { name: "", print_name: "d(PC)" },
+    Op { name: "", print_name: "d(PC,Dn)" },
+    Op { name: "", print_name: "#xxx" },
+];
+
+// The 68000 modes followed by the full format ones of the 68020
+const MODES_68020: &[Op] = &[
+    Op { name: "", print_name: "Dn" },
+    Op { name: "", print_name: "An" },
+    Op { name: "", print_name: "(An)" },
+    Op { name: "", print_name: "(An)+" },
+    Op { name: "", print_name: "-(An)" },
+    Op { name: "", print_name: "d(An)" },
+    Op { name: "", print_name: "d(An,Dn)" },
+    Op { name: "", print_name: "xxx.W" },
+    Op { name: "", print_name: "xxx.L" },
+    Op { name: "", print_name: "d(PC)" },
+    Op { name: "", print_name: "d(PC,Dn)" },
+    Op { name: "", print_name: "#xxx" },
+    Op { name: "", print_name: "(d8,An,Dn.L*4)" },
+    Op { name: "", print_name: "(bd,An,Xn)" },
+    Op { name: "", print_name: "([bd,An],Xn,od)" },
+    Op { name: "", print_name: "([bd,An,Xn],od)" },
+    Op { name: "", print_name: "(d8,PC,Dn.L*4)" },
+    Op { name: "", print_name: "(bd,PC,Xn)" },
+    Op { name: "", print_name: "([bd,PC],Xn,od)" },
+    Op { name: "", print_name: "([bd,PC,Xn],od)" },
+];
+
+const SIZES: &[Op] = &[
+    Op { name: "", print_name: "Byte, Word" },
+    Op { name: "", print_name: "Long" },
+];
+
+const TEXT: &str = "Effective address calculation time of a source operand, derived from the \
+                            measured tables as the difference to a Dn source that is seen most often. \
+                            Instructions list their timings as base + EA below their tables.";
+
+// EA time per (mode, long)
+pub struct EaTimes {
+    times: HashMap<(&'static str, bool), usize>,
+}
+
+impl EaTimes {
+    pub fn get(&self, mode: &'static str, long: bool) -> Option<usize> {
+        self.times.get(&(mode, long)).cloned()
+    }
+}
+
+// A cell that isn't base + EA
+pub struct Anomaly {
+    pub src: &'static str,
+    pub dst: &'static str,
+    pub measured: usize,
+    pub expected: usize,
+}
+
+pub struct Decomposition {
+    // Base time of every destination column that has one
+    pub bases: Vec<(&'static str, usize)>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+fn is_long(table: &TimingTable) -> bool {
+    table.size == Some(Size::Long)
+}
+
+fn is_mode(name: &str) -> bool {
+    MODES.iter().any(|mode| mode.print_name == name)
+}
+
+// The value seen most often, the smallest one on a tie
+fn most_common(votes: &HashMap<i64, usize>) -> Option<i64> {
+    votes.iter().max_by_key(|&(&value, &count)| (count, -value)).map(|(&value, _)| value)
+}
+
+// Measured cycles of a grid table per (source, destination) column
+fn grid_cells(table: &TimingTable) -> Vec<(&'static str, Vec<(&'static str, usize)>)> {
+    let (src_table, dst_table) = match table.layout {
+        TableLayout::Grid(src, dst) => (src, dst),
+        _ => return Vec::new(),
+    };
+
+    if !src_table.iter().any(|src| is_mode(src.print_name)) {
+        return Vec::new();
+    }
+
+    dst_table.iter().enumerate().map(|(col, dst)| {
+        let cells = src_table.iter().enumerate()
+            .filter_map(|(row, src)| {
+                table.statements[row * dst_table.len() + col].cycle_count.map(|cycles| (src.print_name, cycles))
+            })
+            .collect();
+
+        (dst.print_name, cells)
+    }).collect()
+}
+
+fn measured_tables<'a, 'b>(tables: &'b [Table<'a>]) -> Vec<&'b TimingTable<'a>> {
+    tables.iter()
+        .filter_map(|table| match *table {
+            Table::Measured(ref table) => Some(table),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(crate) fn derive(docs: &[(&Instruction, Vec<Table>)]) -> EaTimes {
+    let mut votes: HashMap<(&'static str, bool), HashMap<i64, usize>> = HashMap::new();
+
+    for (_, tables) in docs {
+        for table in measured_tables(tables) {
+            for (_, cells) in grid_cells(table) {
+                let base = match cells.iter().find(|cell| cell.0 == "Dn") {
+                    Some(&(_, base)) => base as i64,
+                    None => continue,
+                };
+
+                for &(src, cycles) in &cells {
+                    let counts = votes.entry((src, is_long(table))).or_default();
+                    *counts.entry(cycles as i64 - base).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    let times = votes.iter()
+        .filter_map(|(&key, counts)| match most_common(counts) {
+            Some(time) if time >= 0 => Some((key, time as usize)),
+            _ => None,
+        })
+        .collect();
+
+    EaTimes { times }
+}
+
+// None for tables that aren't a grid over source addressing modes or have no
+// destination with a base time
+pub(crate) fn decompose(table: &TimingTable, times: &EaTimes) -> Option<Decomposition> {
+    let columns = grid_cells(table);
+
+    if columns.is_empty() {
+        return None;
+    }
+
+    let long = is_long(table);
+    let mut bases = Vec::new();
+    let mut anomalies = Vec::new();
+
+    for (dst, cells) in columns {
+        let mut votes = HashMap::new();
+
+        for &(src, cycles) in &cells {
+            if let Some(time) = times.get(src, long) {
+                *votes.entry(cycles as i64 - time as i64).or_insert(0) += 1;
+            }
+        }
+
+        let base = match most_common(&votes) {
+            Some(base) if base >= 0 => base as usize,
+            _ => continue,
+        };
+
+        bases.push((dst, base));
+
+        for &(src, cycles) in &cells {
+            match times.get(src, long) {
+                Some(time) if base + time == cycles => (),
+                time => anomalies.push(Anomaly {
+                    src,
+                    dst,
+                    measured: cycles,
+                    expected: base + time.unwrap_or(0),
+                }),
+            }
+        }
+    }
+
+    if bases.is_empty() {
+        return None;
+    }
+
+    Some(Decomposition { bases, anomalies })
+}
+
+pub(crate) fn generate_chapter<'a>(cpu: Cpu, times: &EaTimes) -> Chapter<'a> {
+    let modes = if cpu >= Cpu::M68EC020 { MODES_68020 } else { MODES };
+    let mut statements = Vec::new();
+
+    for mode in modes {
+        for (size, long) in SIZES.iter().zip(&[false, true]) {
+            statements.push(BuildResult {
+                src: Some(*mode),
+                dst: *size,
+                statement: format!("{} {}", mode.print_name, size.print_name),
+                code: Vec::new(),
+                preset: None,
+                cycle_count: times.get(mode.print_name, *long),
+                bus: None,
+                fault: None,
+                trace: Vec::new(),
+                cases: None,
+                rejection: None,
+            });
+        }
+    }
+
+    Chapter {
+        title: "Effective Address Calculation",
+        text: TEXT,
+        tables: vec![Table::Measured(TimingTable {
+            name: "ea".to_owned(),
+            size: None,
+            layout: TableLayout::Grid(modes, SIZES),
+            statements,
+            caption: None,
+        })],
+    }
+}
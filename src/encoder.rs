@@ -0,0 +1,1357 @@
+// Built-in 68000 instruction encoder, an alternative to running vasm.
+//
+// Covers the statements the generator builds for the 68000: the instruction set with its
+// addressing modes, register lists, branches to *+n and dc.w. Operand and size combinations
+// the 68000 doesn't have are rejected like an assembler would, anything beyond that (68010
+// and 68020 instructions, full format addressing modes) is reported as unsupported.
+
+use descriptions::Size;
+
+pub enum EncodeError {
+    // Addressing modes the instruction doesn't take
+    IllegalOperands,
+    // Operation size the instruction doesn't have
+    IllegalSize,
+    // Operand or number that can't be parsed
+    Syntax(String),
+    // Instruction or addressing mode the encoder doesn't know
+    Unsupported,
+}
+
+impl EncodeError {
+    pub fn describe(&self) -> String {
+        match *self {
+            EncodeError::IllegalOperands => "illegal operands".to_owned(),
+            EncodeError::IllegalSize => "illegal size".to_owned(),
+            EncodeError::Syntax(ref text) => format!("syntax error in '{}'", text),
+            EncodeError::Unsupported => "not supported by the built-in encoder".to_owned(),
+        }
+    }
+}
+
+// Index register of d8(An,Xn) and d8(PC,Xn), 0-7 are d0-d7 and 8-15 a0-a7
+#[derive(Copy, Clone, PartialEq)]
+struct IndexReg {
+    reg: u16,
+    long: bool,
+}
+
+// Registers are numbered 0-7 for d0-d7 and 8-15 for a0-a7
+#[derive(Copy, Clone, PartialEq)]
+enum Operand {
+    DataReg(u16),
+    AddrReg(u16),
+    Indirect(u16),
+    PostInc(u16),
+    PreDec(u16),
+    Disp(i64, u16),
+    Index(i64, u16, IndexReg),
+    AbsShort(i64),
+    AbsLong(i64),
+    PcDisp(i64),
+    PcIndex(i64, IndexReg),
+    Immediate(i64),
+    Ccr,
+    Sr,
+    Usp,
+    // Bit n set for register n
+    RegList(u16),
+    // Branch target relative to the start of the instruction, *+n
+    Relative(i64),
+}
+
+// Mode, register and extension words of an effective address
+struct Ea {
+    mode: u16,
+    reg: u16,
+    ext: Vec<u16>,
+}
+
+impl Ea {
+    fn bits(&self) -> u16 {
+        (self.mode << 3) | self.reg
+    }
+}
+
+// Addressing categories of the 68000 manual
+const DATA: u8 = 1;
+const MEMORY: u8 = 2;
+const CONTROL: u8 = 4;
+const ALTERABLE: u8 = 8;
+
+const ALL_SIZES: &[Size] = &[Size::Byte, Size::Word, Size::Long];
+const WORD_LONG: &[Size] = &[Size::Word, Size::Long];
+
+// Mnemonics of the 68010 and 68020 the encoder leaves to vasm
+const UNSUPPORTED: &[&str] = &[
+    "bkpt", "rtd", "movec", "moves", "chk2", "cmp2", "extb", "trapf", "trapt", "callm", "rtm",
+    "cas", "cas2", "pack", "unpk", "bfchg", "bfclr", "bfexts", "bfextu", "bfffo", "bfins", "bfset", "bftst",
+];
+
+fn syntax(text: &str) -> EncodeError {
+    EncodeError::Syntax(text.to_owned())
+}
+
+// Decimal or $ prefixed hex number, optionally negative
+fn parse_number(text: &str) -> Result<i64, EncodeError> {
+    let (negative, digits) = match text.starts_with('-') {
+        true => (true, &text[1..]),
+        false => (false, text),
+    };
+
+    let value = match digits.strip_prefix('$') {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None => digits.parse::<i64>(),
+    };
+
+    match value {
+        Ok(value) if negative => Ok(-value),
+        Ok(value) => Ok(value),
+        Err(_) => Err(syntax(text)),
+    }
+}
+
+fn parse_register(text: &str) -> Option<u16> {
+    if text == "sp" {
+        return Some(15);
+    }
+
+    let mut chars = text.chars();
+
+    let base = match chars.next() {
+        Some('d') => 0,
+        Some('a') => 8,
+        _ => return None,
+    };
+
+    match (chars.next().and_then(|c| c.to_digit(10)), chars.next()) {
+        (Some(n), None) if n < 8 => Some(base + n as u16),
+        _ => None,
+    }
+}
+
+// d0-d7/a0-a6 style list
+fn parse_register_list(text: &str) -> Option<u16> {
+    let mut mask = 0;
+
+    for part in text.split('/') {
+        let mut range = part.splitn(2, '-');
+        let first = parse_register(range.next().unwrap())?;
+        let last = match range.next() {
+            Some(last) => parse_register(last)?,
+            None => first,
+        };
+
+        if last < first {
+            return None;
+        }
+
+        for reg in first..last + 1 {
+            mask |= 1 << reg;
+        }
+    }
+
+    Some(mask)
+}
+
+// d0, d0.w or a1.l. Scaled indexes are 68020 only
+fn parse_index(text: &str) -> Result<IndexReg, EncodeError> {
+    if text.contains('*') {
+        return Err(EncodeError::Unsupported);
+    }
+
+    let mut parts = text.splitn(2, '.');
+    let reg = parse_register(parts.next().unwrap()).ok_or(syntax(text))?;
+
+    let long = match parts.next() {
+        None | Some("w") => false,
+        Some("l") => true,
+        Some(_) => return Err(syntax(text)),
+    };
+
+    Ok(IndexReg { reg, long })
+}
+
+fn address_register(text: &str) -> Result<u16, EncodeError> {
+    match parse_register(text) {
+        Some(reg) if reg >= 8 => Ok(reg),
+        _ => Err(syntax(text)),
+    }
+}
+
+// d(An), d(An,Xn), d(PC) and d(PC,Xn)
+fn parse_displacement(text: &str) -> Result<Operand, EncodeError> {
+    let open = text.find('(').ok_or(syntax(text))?;
+    let disp = match &text[..open] {
+        "" => 0,
+        disp => parse_number(disp)?,
+    };
+
+    let inner: Vec<&str> = text[open + 1..text.len() - 1].split(',').collect();
+
+    match (inner[0], inner.len()) {
+        ("pc", 1) => Ok(Operand::PcDisp(disp)),
+        ("pc", 2) => Ok(Operand::PcIndex(disp, parse_index(inner[1])?)),
+        (base, 1) => Ok(Operand::Disp(disp, address_register(base)?)),
+        (base, 2) => Ok(Operand::Index(disp, address_register(base)?, parse_index(inner[1])?)),
+        _ => Err(syntax(text)),
+    }
+}
+
+fn parse_operand(text: &str) -> Result<Operand, EncodeError> {
+    let text = text.trim();
+
+    match text {
+        "ccr" => return Ok(Operand::Ccr),
+        "sr" => return Ok(Operand::Sr),
+        "usp" => return Ok(Operand::Usp),
+        _ => (),
+    }
+
+    if let Some(value) = text.strip_prefix('#') {
+        return Ok(Operand::Immediate(parse_number(value)?));
+    }
+
+    if let Some(offset) = text.strip_prefix('*') {
+        return Ok(Operand::Relative(match offset {
+            "" => 0,
+            _ => parse_number(offset.strip_prefix('+').unwrap_or(offset))?,
+        }));
+    }
+
+    if let Some(reg) = parse_register(text) {
+        return Ok(if reg < 8 { Operand::DataReg(reg) } else { Operand::AddrReg(reg) });
+    }
+
+    if let Some(mask) = parse_register_list(text) {
+        return Ok(Operand::RegList(mask));
+    }
+
+    // Full format modes, ([bd,An],Xn,od) or (bd,An,Xn)
+    if text.contains('[') || (text.starts_with('(') && text.contains(',')) {
+        return Err(EncodeError::Unsupported);
+    }
+
+    if text.starts_with("-(") && text.ends_with(')') {
+        return Ok(Operand::PreDec(address_register(&text[2..text.len() - 1])?));
+    }
+
+    if text.starts_with('(') && text.ends_with(")+") {
+        return Ok(Operand::PostInc(address_register(&text[1..text.len() - 2])?));
+    }
+
+    if text.starts_with('(') && text.ends_with(')') {
+        return match &text[1..text.len() - 1] {
+            "pc" => Ok(Operand::PcDisp(0)),
+            reg => Ok(Operand::Indirect(address_register(reg)?)),
+        };
+    }
+
+    if text.ends_with(')') {
+        return parse_displacement(text);
+    }
+
+    if let Some(address) = text.strip_suffix(".w") {
+        Ok(Operand::AbsShort(parse_number(address)?))
+    } else {
+        Ok(Operand::AbsLong(parse_number(text.strip_suffix(".l").unwrap_or(text))?))
+    }
+}
+
+// Operands are separated by commas outside of parentheses
+fn split_operands(text: &str) -> Vec<&str> {
+    let mut operands = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                operands.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    if !text.is_empty() {
+        operands.push(&text[start..]);
+    }
+
+    operands
+}
+
+fn categories(op: &Operand) -> u8 {
+    match *op {
+        Operand::DataReg(_) => DATA | ALTERABLE,
+        Operand::AddrReg(_) => ALTERABLE,
+        Operand::Indirect(_) | Operand::Disp(..) | Operand::Index(..) | Operand::AbsShort(_) | Operand::AbsLong(_) => {
+            DATA | MEMORY | CONTROL | ALTERABLE
+        }
+        Operand::PostInc(_) | Operand::PreDec(_) => DATA | MEMORY | ALTERABLE,
+        Operand::PcDisp(_) | Operand::PcIndex(..) => DATA | MEMORY | CONTROL,
+        Operand::Immediate(_) => DATA | MEMORY,
+        _ => 0,
+    }
+}
+
+fn check(op: &Operand, required: u8) -> Result<(), EncodeError> {
+    if categories(op) & required == required && categories(op) != 0 {
+        Ok(())
+    } else {
+        Err(EncodeError::IllegalOperands)
+    }
+}
+
+fn brief_extension(disp: i64, index: IndexReg) -> Result<u16, EncodeError> {
+    if !(-128..=127).contains(&disp) {
+        return Err(EncodeError::IllegalOperands);
+    }
+
+    Ok((if index.reg >= 8 { 0x8000 } else { 0 }) |
+       ((index.reg & 7) << 12) |
+       (if index.long { 0x800 } else { 0 }) |
+       (disp as u16 & 0xff))
+}
+
+fn word(value: i64) -> Result<u16, EncodeError> {
+    if !(-32768..=65535).contains(&value) {
+        return Err(EncodeError::IllegalOperands);
+    }
+
+    Ok(value as u16)
+}
+
+// Signed or unsigned values of the size, like vasm accepts them
+fn immediate(value: i64, size: Size) -> Result<Vec<u16>, EncodeError> {
+    let range = match size {
+        Size::Byte => -0x80..=0xff,
+        Size::Word => -0x8000..=0xffff,
+        Size::Long => -0x8000_0000..=0xffff_ffff,
+    };
+
+    if !range.contains(&value) {
+        return Err(EncodeError::IllegalOperands);
+    }
+
+    Ok(match size {
+        Size::Byte => vec![value as u16 & 0xff],
+        Size::Word => vec![value as u16],
+        Size::Long => vec![(value >> 16) as u16, value as u16],
+    })
+}
+
+// Effective address of an operand, size is the size of an immediate
+fn ea(op: &Operand, size: Size) -> Result<Ea, EncodeError> {
+    let (mode, reg, ext) = match *op {
+        Operand::DataReg(n) => (0, n, Vec::new()),
+        Operand::AddrReg(n) => (1, n - 8, Vec::new()),
+        Operand::Indirect(n) => (2, n - 8, Vec::new()),
+        Operand::PostInc(n) => (3, n - 8, Vec::new()),
+        Operand::PreDec(n) => (4, n - 8, Vec::new()),
+        Operand::Disp(disp, n) if (-32768..=32767).contains(&disp) => (5, n - 8, vec![disp as u16]),
+        Operand::Index(disp, n, index) => (6, n - 8, vec![brief_extension(disp, index)?]),
+        Operand::AbsShort(address) if (-32768..=32767).contains(&address) => (7, 0, vec![address as u16]),
+        Operand::AbsLong(address) => (7, 1, vec![(address >> 16) as u16, address as u16]),
+        Operand::PcDisp(disp) if (-32768..=32767).contains(&disp) => (7, 2, vec![disp as u16]),
+        Operand::PcIndex(disp, index) => (7, 3, vec![brief_extension(disp, index)?]),
+        Operand::Immediate(value) => (7, 4, immediate(value, size)?),
+        _ => return Err(EncodeError::IllegalOperands),
+    };
+
+    Ok(Ea { mode, reg, ext })
+}
+
+fn sized(size: Option<Size>, allowed: &[Size], default: Size) -> Result<Size, EncodeError> {
+    match size {
+        None => Ok(default),
+        Some(size) if allowed.contains(&size) => Ok(size),
+        Some(_) => Err(EncodeError::IllegalSize),
+    }
+}
+
+// Size field of most instructions
+fn size_bits(size: Size) -> u16 {
+    match size {
+        Size::Byte => 0,
+        Size::Word => 1,
+        Size::Long => 2,
+    }
+}
+
+fn condition(cc: &str) -> Option<u16> {
+    let conditions = ["t", "f", "hi", "ls", "cc", "cs", "ne", "eq", "vc", "vs", "pl", "mi", "ge", "lt", "gt", "le"];
+
+    match cc {
+        "hs" => Some(4),
+        "lo" => Some(5),
+        _ => conditions.iter().position(|&c| c == cc).map(|i| i as u16),
+    }
+}
+
+fn operands(ops: &[Operand], count: usize) -> Result<&[Operand], EncodeError> {
+    if ops.len() == count { Ok(ops) } else { Err(EncodeError::IllegalOperands) }
+}
+
+// Opcode followed by the extension words of the effective addresses in order
+fn instruction(opcode: u16, eas: &[&Ea]) -> Vec<u16> {
+    let mut words = vec![opcode];
+
+    for ea in eas {
+        words.extend_from_slice(&ea.ext);
+    }
+
+    words
+}
+
+fn data_register(op: &Operand) -> Result<u16, EncodeError> {
+    match *op {
+        Operand::DataReg(n) => Ok(n),
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+fn address_register_number(op: &Operand) -> Result<u16, EncodeError> {
+    match *op {
+        Operand::AddrReg(n) => Ok(n - 8),
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+// Byte operations can't read an address register
+fn check_byte_source(src: &Operand, size: Size) -> Result<(), EncodeError> {
+    match (*src, size) {
+        (Operand::AddrReg(_), Size::Byte) => Err(EncodeError::IllegalSize),
+        _ => Ok(()),
+    }
+}
+
+// ORI, ANDI, SUBI, ADDI, EORI and CMPI, including the CCR and SR forms of the logical ones
+fn encode_immediate(base: u16, logical: bool, size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let value = match *src {
+        Operand::Immediate(value) => value,
+        _ => return Err(EncodeError::IllegalOperands),
+    };
+
+    match *dst {
+        Operand::Ccr if logical => {
+            sized(size, &[Size::Byte], Size::Byte)?;
+            Ok(vec![base | 0x3c, immediate(value, Size::Byte)?[0]])
+        }
+        Operand::Sr if logical => {
+            sized(size, &[Size::Word], Size::Word)?;
+            Ok(vec![base | 0x7c, immediate(value, Size::Word)?[0]])
+        }
+        _ => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            check(dst, DATA | ALTERABLE)?;
+            let dst = ea(dst, size)?;
+
+            let mut words = vec![base | (size_bits(size) << 6) | dst.bits()];
+            words.extend(immediate(value, size)?);
+            words.extend(dst.ext);
+            Ok(words)
+        }
+    }
+}
+
+// ADDA, SUBA and CMPA
+fn encode_address(base: u16, size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let size = sized(size, WORD_LONG, Size::Word)?;
+    let an = address_register_number(dst)?;
+    check(src, 0)?;
+    let src = ea(src, size)?;
+
+    let long = if size == Size::Long { 0x100 } else { 0 };
+    Ok(instruction(base | (an << 9) | long | 0xc0 | src.bits(), &[&src]))
+}
+
+// ADD, SUB, AND and OR in all their forms
+fn encode_arithmetic(name: &str, size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let (base, immediate_base, logical) = match name {
+        "add" => (0xd000, 0x0600, false),
+        "sub" => (0x9000, 0x0400, false),
+        "and" => (0xc000, 0x0200, true),
+        _ => (0x8000, 0x0000, true),
+    };
+
+    match (*src, *dst) {
+        (_, Operand::AddrReg(_)) if logical => Err(EncodeError::IllegalOperands),
+        (_, Operand::AddrReg(_)) => encode_address(base, size, src, dst),
+        (Operand::Immediate(_), Operand::DataReg(_)) | (_, Operand::DataReg(_)) => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            let dn = data_register(dst)?;
+            check(src, if logical { DATA } else { 0 })?;
+            check_byte_source(src, size)?;
+            let src = ea(src, size)?;
+
+            Ok(instruction(base | (dn << 9) | (size_bits(size) << 6) | src.bits(), &[&src]))
+        }
+        (Operand::Immediate(_), _) => encode_immediate(immediate_base, logical, size, src, dst),
+        (Operand::DataReg(dn), _) => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            check(dst, MEMORY | ALTERABLE)?;
+            let dst = ea(dst, size)?;
+
+            Ok(instruction(base | (dn << 9) | ((4 + size_bits(size)) << 6) | dst.bits(), &[&dst]))
+        }
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+fn encode_cmp(size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    match (*src, *dst) {
+        (_, Operand::AddrReg(_)) => encode_address(0xb000, size, src, dst),
+        (Operand::PostInc(ay), Operand::PostInc(ax)) => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            Ok(vec![0xb108 | ((ax - 8) << 9) | (size_bits(size) << 6) | (ay - 8)])
+        }
+        (_, Operand::DataReg(dn)) => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            check(src, 0)?;
+            check_byte_source(src, size)?;
+            let src = ea(src, size)?;
+
+            Ok(instruction(0xb000 | (dn << 9) | (size_bits(size) << 6) | src.bits(), &[&src]))
+        }
+        (Operand::Immediate(_), _) => encode_immediate(0x0c00, false, size, src, dst),
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+// BTST, BCHG, BCLR and BSET. The size is long for a data register and byte in memory
+fn encode_bit(name: &str, size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let kind = match name {
+        "btst" => 0x00,
+        "bchg" => 0x40,
+        "bclr" => 0x80,
+        _ => 0xc0,
+    };
+
+    check(dst, DATA)?;
+
+    match *dst {
+        Operand::DataReg(_) => sized(size, &[Size::Long], Size::Long)?,
+        _ => sized(size, &[Size::Byte], Size::Byte)?,
+    };
+
+    let test = name == "btst";
+
+    match *src {
+        Operand::DataReg(dn) => {
+            check(dst, if test { DATA } else { DATA | ALTERABLE })?;
+            let dst = ea(dst, Size::Byte)?;
+            Ok(instruction(0x0100 | (dn << 9) | kind | dst.bits(), &[&dst]))
+        }
+        Operand::Immediate(bit) => {
+            match *dst {
+                Operand::Immediate(_) => return Err(EncodeError::IllegalOperands),
+                _ => check(dst, if test { DATA } else { DATA | ALTERABLE })?,
+            }
+
+            let dst = ea(dst, Size::Byte)?;
+            let mut words = vec![0x0800 | kind | dst.bits(), bit as u16 & 0xff];
+            words.extend(dst.ext);
+            Ok(words)
+        }
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+fn encode_move(size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    match (*src, *dst) {
+        (_, Operand::Ccr) | (_, Operand::Sr) => {
+            sized(size, &[Size::Word], Size::Word)?;
+            check(src, DATA)?;
+            let base = if *dst == Operand::Ccr { 0x44c0 } else { 0x46c0 };
+            let src = ea(src, Size::Word)?;
+            Ok(instruction(base | src.bits(), &[&src]))
+        }
+        (Operand::Sr, _) => {
+            sized(size, &[Size::Word], Size::Word)?;
+            check(dst, DATA | ALTERABLE)?;
+            let dst = ea(dst, Size::Word)?;
+            Ok(instruction(0x40c0 | dst.bits(), &[&dst]))
+        }
+        (Operand::AddrReg(an), Operand::Usp) => {
+            sized(size, &[Size::Long], Size::Long)?;
+            Ok(vec![0x4e60 | (an - 8)])
+        }
+        (Operand::Usp, Operand::AddrReg(an)) => {
+            sized(size, &[Size::Long], Size::Long)?;
+            Ok(vec![0x4e68 | (an - 8)])
+        }
+        (_, Operand::AddrReg(_)) => encode_movea(size, src, dst),
+        _ => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            check(src, 0)?;
+            check(dst, DATA | ALTERABLE)?;
+            check_byte_source(src, size)?;
+            let src = ea(src, size)?;
+            let dst = ea(dst, size)?;
+
+            Ok(instruction(move_size(size) | (dst.reg << 9) | (dst.mode << 6) | src.bits(), &[&src, &dst]))
+        }
+    }
+}
+
+// MOVE has its own size field
+fn move_size(size: Size) -> u16 {
+    match size {
+        Size::Byte => 0x1000,
+        Size::Word => 0x3000,
+        Size::Long => 0x2000,
+    }
+}
+
+fn encode_movea(size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let size = sized(size, WORD_LONG, Size::Word)?;
+    let an = address_register_number(dst)?;
+    check(src, 0)?;
+    let src = ea(src, size)?;
+
+    Ok(instruction(move_size(size) | (an << 9) | 0x40 | src.bits(), &[&src]))
+}
+
+fn encode_movep(size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let long = sized(size, WORD_LONG, Size::Word)? == Size::Long;
+
+    let (dn, disp, an, to_memory) = match (*src, *dst) {
+        (Operand::DataReg(dn), Operand::Disp(disp, an)) => (dn, disp, an, true),
+        (Operand::DataReg(dn), Operand::Indirect(an)) => (dn, 0, an, true),
+        (Operand::Disp(disp, an), Operand::DataReg(dn)) => (dn, disp, an, false),
+        (Operand::Indirect(an), Operand::DataReg(dn)) => (dn, 0, an, false),
+        _ => return Err(EncodeError::IllegalOperands),
+    };
+
+    let opmode = 4 | (if to_memory { 2 } else { 0 }) | (if long { 1 } else { 0 });
+    Ok(vec![0x0008 | (dn << 9) | (opmode << 6) | (an - 8), word(disp)?])
+}
+
+fn register_mask(op: &Operand) -> Option<u16> {
+    match *op {
+        Operand::RegList(mask) => Some(mask),
+        Operand::DataReg(n) | Operand::AddrReg(n) => Some(1 << n),
+        _ => None,
+    }
+}
+
+fn encode_movem(size: Option<Size>, src: &Operand, dst: &Operand) -> Result<Vec<u16>, EncodeError> {
+    let long = if sized(size, WORD_LONG, Size::Word)? == Size::Long { 0x40 } else { 0 };
+
+    let (base, mask, memory) = match (register_mask(src), register_mask(dst)) {
+        (Some(mask), None) => {
+            match *dst {
+                Operand::PreDec(_) => (0x4880, mask.reverse_bits(), dst),
+                _ => {
+                    check(dst, CONTROL | ALTERABLE)?;
+                    (0x4880, mask, dst)
+                }
+            }
+        }
+        (None, Some(mask)) => {
+            match *src {
+                Operand::PostInc(_) => (0x4c80, mask, src),
+                _ => {
+                    check(src, CONTROL)?;
+                    (0x4c80, mask, src)
+                }
+            }
+        }
+        _ => return Err(EncodeError::IllegalOperands),
+    };
+
+    let memory = ea(memory, Size::Word)?;
+    let mut words = vec![base | long | memory.bits(), mask];
+    words.extend(memory.ext);
+    Ok(words)
+}
+
+// Type and direction of ASL/ASR, LSL/LSR, ROXL/ROXR and ROL/ROR
+fn shift(name: &str) -> Option<(u16, u16)> {
+    let (kind, left) = match (name.strip_suffix('l'), name.strip_suffix('r')) {
+        (Some(kind), _) => (kind, 1),
+        (_, Some(kind)) => (kind, 0),
+        _ => return None,
+    };
+
+    match kind {
+        "as" => Some((0, left)),
+        "ls" => Some((1, left)),
+        "rox" => Some((2, left)),
+        "ro" => Some((3, left)),
+        _ => None,
+    }
+}
+
+fn encode_shift(kind: u16, left: u16, size: Option<Size>, ops: &[Operand]) -> Result<Vec<u16>, EncodeError> {
+    match ops.len() {
+        1 => {
+            sized(size, &[Size::Word], Size::Word)?;
+            check(&ops[0], MEMORY | ALTERABLE)?;
+            let dst = ea(&ops[0], Size::Word)?;
+            Ok(instruction(0xe0c0 | (kind << 9) | (left << 8) | dst.bits(), &[&dst]))
+        }
+        2 => {
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+            let dy = data_register(&ops[1])?;
+
+            let (count, register) = match ops[0] {
+                Operand::Immediate(n) if (1..=8).contains(&n) => (n as u16 & 7, 0),
+                Operand::DataReg(dx) => (dx, 0x20),
+                _ => return Err(EncodeError::IllegalOperands),
+            };
+
+            Ok(vec![0xe000 | (count << 9) | (left << 8) | (size_bits(size) << 6) | register | (kind << 3) | dy])
+        }
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+// Bcc, BRA and BSR to *+n. Byte displacements of 0 and -1 select the longer forms
+fn encode_branch(cc: u16, size: Option<Size>, ops: &[Operand]) -> Result<Vec<u16>, EncodeError> {
+    let size = sized(size, &[Size::Byte, Size::Word], Size::Word)?;
+
+    let disp = match operands(ops, 1)?[0] {
+        Operand::Relative(offset) => offset - 2,
+        _ => return Err(EncodeError::IllegalOperands),
+    };
+
+    match size {
+        Size::Byte if (-128..=127).contains(&disp) && disp != 0 && disp != -1 => {
+            Ok(vec![0x6000 | (cc << 8) | (disp as u16 & 0xff)])
+        }
+        Size::Word if (-32768..=32767).contains(&disp) => Ok(vec![0x6000 | (cc << 8), disp as u16]),
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+fn encode_dbcc(cc: u16, size: Option<Size>, ops: &[Operand]) -> Result<Vec<u16>, EncodeError> {
+    sized(size, &[Size::Word], Size::Word)?;
+    let ops = operands(ops, 2)?;
+    let dn = data_register(&ops[0])?;
+
+    match ops[1] {
+        Operand::Relative(offset) if (-32768..=32767).contains(&(offset - 2)) => {
+            Ok(vec![0x50c8 | (cc << 8) | dn, (offset - 2) as u16])
+        }
+        _ => Err(EncodeError::IllegalOperands),
+    }
+}
+
+// One operand instructions that take a data alterable destination
+fn encode_single(base: u16, size: Size, op: &Operand, sized_opcode: bool) -> Result<Vec<u16>, EncodeError> {
+    check(op, DATA | ALTERABLE)?;
+    let dst = ea(op, size)?;
+    let size = if sized_opcode { size_bits(size) << 6 } else { 0 };
+    Ok(instruction(base | size | dst.bits(), &[&dst]))
+}
+
+fn encode_instruction(name: &str, size: Option<Size>, ops: &[Operand]) -> Result<Vec<u16>, EncodeError> {
+    let no_operands = |opcode: u16| -> Result<Vec<u16>, EncodeError> {
+        operands(ops, 0)?;
+        sized(size, &[], Size::Word)?;
+        Ok(vec![opcode])
+    };
+
+    match name {
+        "abcd" | "sbcd" | "addx" | "subx" => {
+            let ops = operands(ops, 2)?;
+
+            let base = match name {
+                "abcd" => 0xc100 | (size_bits(sized(size, &[Size::Byte], Size::Byte)?) << 6),
+                "sbcd" => 0x8100 | (size_bits(sized(size, &[Size::Byte], Size::Byte)?) << 6),
+                "addx" => 0xd100 | (size_bits(sized(size, ALL_SIZES, Size::Word)?) << 6),
+                _ => 0x9100 | (size_bits(sized(size, ALL_SIZES, Size::Word)?) << 6),
+            };
+
+            match (ops[0], ops[1]) {
+                (Operand::DataReg(y), Operand::DataReg(x)) => Ok(vec![base | (x << 9) | y]),
+                (Operand::PreDec(y), Operand::PreDec(x)) => Ok(vec![base | ((x - 8) << 9) | 8 | (y - 8)]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "add" | "sub" | "and" | "or" => {
+            let ops = operands(ops, 2)?;
+            encode_arithmetic(name, size, &ops[0], &ops[1])
+        }
+
+        "adda" | "suba" | "cmpa" => {
+            let ops = operands(ops, 2)?;
+            let base = match name { "adda" => 0xd000, "suba" => 0x9000, _ => 0xb000 };
+            encode_address(base, size, &ops[0], &ops[1])
+        }
+
+        "ori" | "andi" | "subi" | "addi" | "eori" | "cmpi" => {
+            let ops = operands(ops, 2)?;
+
+            let (base, logical) = match name {
+                "ori" => (0x0000, true),
+                "andi" => (0x0200, true),
+                "subi" => (0x0400, false),
+                "addi" => (0x0600, false),
+                "eori" => (0x0a00, true),
+                _ => (0x0c00, false),
+            };
+
+            encode_immediate(base, logical, size, &ops[0], &ops[1])
+        }
+
+        "addq" | "subq" => {
+            let ops = operands(ops, 2)?;
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+
+            let data = match ops[0] {
+                Operand::Immediate(n) if (1..=8).contains(&n) => n as u16 & 7,
+                _ => return Err(EncodeError::IllegalOperands),
+            };
+
+            if let Operand::AddrReg(_) = ops[1] {
+                if size == Size::Byte {
+                    return Err(EncodeError::IllegalSize);
+                }
+            }
+
+            check(&ops[1], ALTERABLE)?;
+            let dst = ea(&ops[1], size)?;
+            let base = if name == "addq" { 0x5000 } else { 0x5100 };
+            Ok(instruction(base | (data << 9) | (size_bits(size) << 6) | dst.bits(), &[&dst]))
+        }
+
+        "eor" => {
+            let ops = operands(ops, 2)?;
+
+            match ops[0] {
+                Operand::Immediate(_) => encode_immediate(0x0a00, true, size, &ops[0], &ops[1]),
+                Operand::DataReg(dn) => {
+                    let size = sized(size, ALL_SIZES, Size::Word)?;
+                    check(&ops[1], DATA | ALTERABLE)?;
+                    let dst = ea(&ops[1], size)?;
+                    Ok(instruction(0xb100 | (dn << 9) | (size_bits(size) << 6) | dst.bits(), &[&dst]))
+                }
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "cmp" => {
+            let ops = operands(ops, 2)?;
+            encode_cmp(size, &ops[0], &ops[1])
+        }
+
+        "cmpm" => {
+            let ops = operands(ops, 2)?;
+
+            match (ops[0], ops[1]) {
+                (Operand::PostInc(_), Operand::PostInc(_)) => encode_cmp(size, &ops[0], &ops[1]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "btst" | "bchg" | "bclr" | "bset" => {
+            let ops = operands(ops, 2)?;
+            encode_bit(name, size, &ops[0], &ops[1])
+        }
+
+        "chk" | "mulu" | "muls" | "divu" | "divs" => {
+            let ops = operands(ops, 2)?;
+            sized(size, &[Size::Word], Size::Word)?;
+
+            let base = match name {
+                "chk" => 0x4180,
+                "mulu" => 0xc0c0,
+                "muls" => 0xc1c0,
+                "divu" => 0x80c0,
+                _ => 0x81c0,
+            };
+
+            let dn = data_register(&ops[1])?;
+            check(&ops[0], DATA)?;
+            let src = ea(&ops[0], Size::Word)?;
+            Ok(instruction(base | (dn << 9) | src.bits(), &[&src]))
+        }
+
+        "clr" | "neg" | "negx" | "not" | "tst" => {
+            let ops = operands(ops, 1)?;
+            let size = sized(size, ALL_SIZES, Size::Word)?;
+
+            let base = match name {
+                "clr" => 0x4200,
+                "neg" => 0x4400,
+                "negx" => 0x4000,
+                "not" => 0x4600,
+                _ => 0x4a00,
+            };
+
+            encode_single(base, size, &ops[0], true)
+        }
+
+        "nbcd" | "tas" => {
+            let ops = operands(ops, 1)?;
+            sized(size, &[Size::Byte], Size::Byte)?;
+            encode_single(if name == "nbcd" { 0x4800 } else { 0x4ac0 }, Size::Byte, &ops[0], false)
+        }
+
+        "ext" => {
+            let dn = data_register(&operands(ops, 1)?[0])?;
+
+            match sized(size, WORD_LONG, Size::Word)? {
+                Size::Long => Ok(vec![0x48c0 | dn]),
+                _ => Ok(vec![0x4880 | dn]),
+            }
+        }
+
+        "swap" => {
+            sized(size, &[Size::Word], Size::Word)?;
+            Ok(vec![0x4840 | data_register(&operands(ops, 1)?[0])?])
+        }
+
+        "exg" => {
+            let ops = operands(ops, 2)?;
+            sized(size, &[Size::Long], Size::Long)?;
+
+            match (ops[0], ops[1]) {
+                (Operand::DataReg(x), Operand::DataReg(y)) => Ok(vec![0xc140 | (x << 9) | y]),
+                (Operand::AddrReg(x), Operand::AddrReg(y)) => Ok(vec![0xc148 | ((x - 8) << 9) | (y - 8)]),
+                (Operand::DataReg(x), Operand::AddrReg(y)) | (Operand::AddrReg(y), Operand::DataReg(x)) => {
+                    Ok(vec![0xc188 | (x << 9) | (y - 8)])
+                }
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "jmp" | "jsr" | "pea" => {
+            let ops = operands(ops, 1)?;
+
+            match name {
+                "pea" => sized(size, &[Size::Long], Size::Long)?,
+                _ => sized(size, &[], Size::Long)?,
+            };
+
+            check(&ops[0], CONTROL)?;
+            let dst = ea(&ops[0], Size::Long)?;
+            let base = match name { "jmp" => 0x4ec0, "jsr" => 0x4e80, _ => 0x4840 };
+            Ok(instruction(base | dst.bits(), &[&dst]))
+        }
+
+        "lea" => {
+            let ops = operands(ops, 2)?;
+            sized(size, &[Size::Long], Size::Long)?;
+            let an = address_register_number(&ops[1])?;
+            check(&ops[0], CONTROL)?;
+            let src = ea(&ops[0], Size::Long)?;
+            Ok(instruction(0x41c0 | (an << 9) | src.bits(), &[&src]))
+        }
+
+        "link" => {
+            let ops = operands(ops, 2)?;
+            sized(size, &[Size::Word], Size::Word)?;
+            let an = address_register_number(&ops[0])?;
+
+            match ops[1] {
+                Operand::Immediate(disp) => Ok(vec![0x4e50 | an, word(disp)?]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "unlk" => {
+            sized(size, &[], Size::Long)?;
+            Ok(vec![0x4e58 | address_register_number(&operands(ops, 1)?[0])?])
+        }
+
+        "move" => {
+            let ops = operands(ops, 2)?;
+            encode_move(size, &ops[0], &ops[1])
+        }
+
+        "movea" => {
+            let ops = operands(ops, 2)?;
+            encode_movea(size, &ops[0], &ops[1])
+        }
+
+        "moveq" => {
+            let ops = operands(ops, 2)?;
+            sized(size, &[Size::Long], Size::Long)?;
+            let dn = data_register(&ops[1])?;
+
+            match ops[0] {
+                Operand::Immediate(value) if (-128..=127).contains(&value) => Ok(vec![0x7000 | (dn << 9) | (value as u16 & 0xff)]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "movep" => {
+            let ops = operands(ops, 2)?;
+            encode_movep(size, &ops[0], &ops[1])
+        }
+
+        "movem" => {
+            let ops = operands(ops, 2)?;
+            encode_movem(size, &ops[0], &ops[1])
+        }
+
+        "bra" => encode_branch(0, size, ops),
+        "bsr" => encode_branch(1, size, ops),
+        "dbra" => encode_dbcc(1, size, ops),
+
+        "trap" => {
+            sized(size, &[], Size::Word)?;
+
+            match operands(ops, 1)?[0] {
+                Operand::Immediate(vector) if (0..=15).contains(&vector) => Ok(vec![0x4e40 | vector as u16]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "stop" => {
+            sized(size, &[], Size::Word)?;
+
+            match operands(ops, 1)?[0] {
+                Operand::Immediate(value) => Ok(vec![0x4e72, word(value)?]),
+                _ => Err(EncodeError::IllegalOperands),
+            }
+        }
+
+        "nop" => no_operands(0x4e71),
+        "illegal" => no_operands(0x4afc),
+        "reset" => no_operands(0x4e70),
+        "rte" => no_operands(0x4e73),
+        "rtr" => no_operands(0x4e77),
+        "rts" => no_operands(0x4e75),
+        "trapv" => no_operands(0x4e76),
+
+        _ => {
+            if let Some((kind, left)) = shift(name) {
+                return encode_shift(kind, left, size, ops);
+            }
+
+            if let Some(cc) = name.strip_prefix("db").and_then(condition) {
+                return encode_dbcc(cc, size, ops);
+            }
+
+            // BT and BF would be BRA and BSR
+            if name.starts_with('b') && name != "bt" && name != "bf" {
+                if let Some(cc) = condition(&name[1..]) {
+                    return encode_branch(cc, size, ops);
+                }
+            }
+
+            if let Some(cc) = name.strip_prefix('s').and_then(condition) {
+                sized(size, &[Size::Byte], Size::Byte)?;
+                return encode_single(0x50c0 | (cc << 8), Size::Byte, &operands(ops, 1)?[0], false);
+            }
+
+            Err(EncodeError::Unsupported)
+        }
+    }
+}
+
+// dc.w with a list of values
+fn encode_data(size: Option<Size>, operands: &str) -> Result<Vec<u16>, EncodeError> {
+    sized(size, &[Size::Word], Size::Word)?;
+    split_operands(operands).iter().map(|value| parse_number(value.trim()).and_then(word)).collect()
+}
+
+// Machine code of a statement, e.g. "add.w d0,(a0)"
+pub fn encode(statement: &str) -> Result<Vec<u8>, EncodeError> {
+    let statement = statement.trim().to_lowercase();
+
+    let (mnemonic, operands) = match statement.find(char::is_whitespace) {
+        Some(i) => (&statement[..i], statement[i..].trim()),
+        None => (&statement[..], ""),
+    };
+
+    let mut parts = mnemonic.splitn(2, '.');
+    let name = parts.next().unwrap();
+
+    let size = match parts.next() {
+        None => None,
+        Some("b") | Some("s") => Some(Size::Byte),
+        Some("w") => Some(Size::Word),
+        Some("l") => Some(Size::Long),
+        Some(_) => return Err(syntax(mnemonic)),
+    };
+
+    // Before the operands, which can be registers the encoder doesn't know, e.g. vbr
+    if UNSUPPORTED.contains(&name) {
+        return Err(EncodeError::Unsupported);
+    }
+
+    let words = if name == "dc" {
+        encode_data(size, operands)?
+    } else {
+        let ops = split_operands(operands).iter().map(|op| parse_operand(op)).collect::<Result<Vec<_>, _>>()?;
+        encode_instruction(name, size, &ops)?
+    };
+
+    Ok(words.iter().flat_map(|word| vec![(word >> 8) as u8, *word as u8]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(statement: &str) -> Vec<u16> {
+        match encode(statement) {
+            Ok(bytes) => bytes.chunks(2).map(|word| ((word[0] as u16) << 8) | word[1] as u16).collect(),
+            Err(err) => panic!("'{}': {}", statement, err.describe()),
+        }
+    }
+
+    fn check(statements: &[(&str, &[u16])]) {
+        for &(statement, expected) in statements {
+            assert_eq!(words(statement), expected, "'{}'", statement);
+        }
+    }
+
+    fn rejected(statements: &[&str], reason: &str) {
+        for statement in statements {
+            match encode(statement) {
+                Ok(bytes) => panic!("'{}' encoded to {:?}", statement, bytes),
+                Err(err) => assert_eq!(err.describe(), reason, "'{}'", statement),
+            }
+        }
+    }
+
+    #[test]
+    fn move_sizes() {
+        check(&[
+            ("move.b d0,d1", &[0x1200]),
+            ("move.w d0,d1", &[0x3200]),
+            ("move.l d0,d1", &[0x2200]),
+            ("move d0,d1", &[0x3200]),
+        ]);
+    }
+
+    #[test]
+    fn move_source_modes() {
+        check(&[
+            ("move.w a0,d1", &[0x3208]),
+            ("move.w (a0),d1", &[0x3210]),
+            ("move.w (a0)+,d1", &[0x3218]),
+            ("move.w -(a0),d1", &[0x3220]),
+            ("move.w 2(a0),d1", &[0x3228, 0x0002]),
+            ("move.w -2(a0),d1", &[0x3228, 0xfffe]),
+            ("move.w 2(a0,d0.w),d1", &[0x3230, 0x0002]),
+            ("move.w 2(a0,d0.l),d1", &[0x3230, 0x0802]),
+            ("move.w -2(a0,a1.w),d1", &[0x3230, 0x90fe]),
+            ("move.w $800.w,d1", &[0x3238, 0x0800]),
+            ("move.w $12345678.l,d1", &[0x3239, 0x1234, 0x5678]),
+            ("move.w $12345678,d1", &[0x3239, 0x1234, 0x5678]),
+            ("move.w 2(pc),d1", &[0x323a, 0x0002]),
+            ("move.w 2(pc,d0.w),d1", &[0x323b, 0x0002]),
+            ("move.w #$1234,d1", &[0x323c, 0x1234]),
+        ]);
+    }
+
+    #[test]
+    fn move_destination_modes() {
+        check(&[
+            ("move.w d0,(a1)", &[0x3280]),
+            ("move.w d0,(a1)+", &[0x32c0]),
+            ("move.w d0,-(a1)", &[0x3300]),
+            ("move.w d0,2(a1)", &[0x3340, 0x0002]),
+            ("move.w d0,2(a1,d0.w)", &[0x3380, 0x0002]),
+            ("move.w d0,$800.w", &[0x31c0, 0x0800]),
+            ("move.w d0,$12345678.l", &[0x33c0, 0x1234, 0x5678]),
+            // The source extension words come first
+            ("move.w 2(a0),4(a1)", &[0x3368, 0x0002, 0x0004]),
+            ("move.l #$12345678,$800.w", &[0x21fc, 0x1234, 0x5678, 0x0800]),
+            ("move.w d0,a1", &[0x3240]),
+            ("move.l (a0),a1", &[0x2250]),
+            ("movea.w d0,a1", &[0x3240]),
+        ]);
+    }
+
+    #[test]
+    fn move_special_registers() {
+        check(&[
+            ("move d0,ccr", &[0x44c0]),
+            ("move.w d0,sr", &[0x46c0]),
+            ("move.w sr,d0", &[0x40c0]),
+            ("move.w #$2700,sr", &[0x46fc, 0x2700]),
+            ("move.l a0,usp", &[0x4e60]),
+            ("move.l usp,a1", &[0x4e69]),
+        ]);
+    }
+
+    #[test]
+    fn immediates() {
+        check(&[
+            ("move.b #$12,d1", &[0x123c, 0x0012]),
+            ("move.b #-1,d1", &[0x123c, 0x00ff]),
+            ("move.b #255,d1", &[0x123c, 0x00ff]),
+            ("move.b #-128,d1", &[0x123c, 0x0080]),
+            ("move.w #$ffff,d1", &[0x323c, 0xffff]),
+            ("move.l #$ffffffff,d1", &[0x223c, 0xffff, 0xffff]),
+            ("move.l #$12345678,d1", &[0x223c, 0x1234, 0x5678]),
+            ("move.l #-2,d1", &[0x223c, 0xffff, 0xfffe]),
+            ("addi.w #1,d0", &[0x0640, 0x0001]),
+            ("addi.b #$ff,(a0)", &[0x0610, 0x00ff]),
+            ("addi.l #$12345678,d0", &[0x0680, 0x1234, 0x5678]),
+            ("subi.w #1,2(a0)", &[0x0468, 0x0001, 0x0002]),
+            ("cmpi.w #1,(a0)+", &[0x0c58, 0x0001]),
+            // ADD to a data register takes the immediate as its source, ADDI is used for memory
+            ("add.w #1,d0", &[0xd07c, 0x0001]),
+            ("add.w #1,(a0)", &[0x0650, 0x0001]),
+            ("ori #$1f,ccr", &[0x003c, 0x001f]),
+            ("andi #$f8ff,sr", &[0x027c, 0xf8ff]),
+            ("eori.b #1,ccr", &[0x0a3c, 0x0001]),
+        ]);
+    }
+
+    #[test]
+    fn register_shifts() {
+        check(&[
+            ("asl.w #1,d0", &[0xe340]),
+            ("asr.b #3,d2", &[0xe602]),
+            ("lsr.l #8,d1", &[0xe089]),
+            ("ror.b d2,d3", &[0xe43b]),
+            ("roxl.w d0,d1", &[0xe171]),
+            ("rol.l d7,d0", &[0xefb8]),
+        ]);
+    }
+
+    #[test]
+    fn memory_shifts() {
+        check(&[
+            ("asl (a0)", &[0xe1d0]),
+            ("lsr.w 2(a0)", &[0xe2e8, 0x0002]),
+            ("roxl.w (a0)+", &[0xe5d8]),
+            ("ror.w $800.w", &[0xe6f8, 0x0800]),
+        ]);
+    }
+
+    #[test]
+    fn branch_displacements() {
+        check(&[
+            ("bra.s *+4", &[0x6002]),
+            ("bne.s *+10", &[0x6608]),
+            ("bsr.s *+129", &[0x617f]),
+            ("bhs.s *+4", &[0x6402]),
+            ("blo.s *-126", &[0x6580]),
+            ("bra *+2", &[0x6000, 0x0000]),
+            ("beq.w *-2", &[0x6700, 0xfffc]),
+            ("bsr.w *+32769", &[0x6100, 0x7fff]),
+        ]);
+    }
+
+    #[test]
+    fn decrement_branch_displacements() {
+        check(&[
+            ("dbra d0,*+2", &[0x51c8, 0x0000]),
+            ("dbf d1,*-4", &[0x51c9, 0xfffa]),
+            ("dbeq d2,*+10", &[0x57ca, 0x0008]),
+            ("dbt d0,*+2", &[0x50c8, 0x0000]),
+        ]);
+    }
+
+    #[test]
+    fn movem_masks() {
+        check(&[
+            // Predecrement reverses the mask, a7 is bit 0 and d0 bit 15
+            ("movem.l d0-d7/a0-a6,-(sp)", &[0x48e7, 0xfffe]),
+            ("movem.w d0,-(a0)", &[0x48a0, 0x8000]),
+            ("movem.l (sp)+,d0-d7/a0-a6", &[0x4cdf, 0x7fff]),
+            ("movem.w d0/a1,(a0)", &[0x4890, 0x0201]),
+            ("movem.l a0,$800.w", &[0x48f8, 0x0100, 0x0800]),
+            // The mask comes before the extension words of the address
+            ("movem.w 2(a0),d1-d3", &[0x4ca8, 0x000e, 0x0002]),
+        ]);
+    }
+
+    #[test]
+    fn quick_ranges() {
+        check(&[
+            ("addq.w #1,d0", &[0x5240]),
+            ("addq.l #8,d0", &[0x5080]),
+            ("addq.w #1,a0", &[0x5248]),
+            ("subq.b #1,(a0)", &[0x5310]),
+            ("subq.l #8,a7", &[0x518f]),
+            ("moveq #0,d0", &[0x7000]),
+            ("moveq #127,d0", &[0x707f]),
+            ("moveq #-1,d1", &[0x72ff]),
+            ("moveq #-128,d7", &[0x7e80]),
+        ]);
+
+        rejected(&["addq.w #0,d0", "addq.w #9,d0", "subq.l #-1,d0", "moveq #128,d0", "moveq #-129,d0"],
+                 "illegal operands");
+    }
+
+    #[test]
+    fn illegal_operands() {
+        rejected(&[
+            // Destinations that aren't data alterable
+            "move.w d0,2(pc)",
+            "move.w d0,#1",
+            "addi.w #1,a0",
+            "clr.w a0",
+            "tst.w #1",
+            "subi.w #1,ccr",
+            // Sources outside the required category
+            "mulu.w a0,d0",
+            "and.w a0,d0",
+            "and.w d0,a0",
+            "lea d0,a0",
+            "jmp (a0)+",
+            "movem.w d0,(a0)+",
+            "movem.w -(a0),d0",
+            // Operands of the wrong kind or out of range
+            "moveq #1,a0",
+            "exg d0,(a0)",
+            "asl.w #9,d0",
+            "asl.w d0",
+            "asl.w d0,(a0)",
+            "dbra a0,*+2",
+            "move.w 128(a0,d0.w),d1",
+            "bra.s *+2",
+            "bra.s *+1",
+            "bra.s *+130",
+            "trap #16",
+            // Immediates that don't fit the size
+            "addi.b #$1234,d0",
+            "move.b #300,d0",
+            "move.b #-129,d0",
+            "addi.w #$12345,d0",
+            "move.l #$100000000,d0",
+            "andi.b #$100,ccr",
+            "ori.w #$10000,sr",
+        ], "illegal operands");
+    }
+
+    #[test]
+    fn illegal_sizes() {
+        rejected(&[
+            "move.b a0,d0",
+            "movea.b d0,a0",
+            "move.b d0,sr",
+            "addq.b #1,a0",
+            "cmp.b a0,d0",
+            "abcd.w d0,d1",
+            "ext.b d0",
+            "swap.l d0",
+            "lea.w (a0),a0",
+            "mulu.l d0,d1",
+            "exg.w d0,d1",
+            "moveq.w #1,d0",
+            "seq.w d0",
+            "asl.l (a0)",
+            "movem.b d0,(a0)",
+            "andi.l #1,ccr",
+            "bra.l *+2",
+            "dbra.l d0,*+2",
+            "nop.w",
+        ], "illegal size");
+    }
+
+    #[test]
+    fn unsupported() {
+        rejected(&["movec vbr,d0", "extb.l d0", "bt.s *+4", "move.w (8,a0,d0.l*4),d1", "move.w ([a0]),d1"],
+                 "not supported by the built-in encoder");
+
+        // Mnemonics too short for a shift or ending in a multibyte character
+        rejected(&["", "é d0", "l d0"], "not supported by the built-in encoder");
+    }
+}
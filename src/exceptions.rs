@@ -0,0 +1,97 @@
+// Exception processing timings. The wrapper points every vector at a NOP in the
+// landing area so the cycles measured for an instruction include the full
+// exception entry (stacking, vector fetch and jump to the handler).
+
+use cpu::Cpu;
+use {assemble_statements, compile_cycle_counts};
+use {BuildResult, Chapter, Context, Op, Preset, Table, TableLayout, TimingTable};
+
+const TEXT: &str = "Instruction and exception entry time, from the start of the instruction until \
+                            the first instruction of the handler is fetched. CHK and DIVU/DIVS use d0 as the \
+                            data register and d1 as the bound/divisor.";
+
+const CASES: &[Op] = &[
+    Op { name: "trap #0", print_name: "TRAP #n" },
+    Op { name: "trapv", print_name: "TRAPV, V clear" },
+    Op { name: "trapv", print_name: "TRAPV, V set" },
+    Op { name: "chk.w d1,d0", print_name: "CHK, in bounds" },
+    Op { name: "chk.w d1,d0", print_name: "CHK, Dn < 0" },
+    Op { name: "chk.w d1,d0", print_name: "CHK, Dn > bound" },
+    Op { name: "illegal", print_name: "ILLEGAL" },
+    Op { name: "dc.w $a000", print_name: "Line-A" },
+    Op { name: "dc.w $f000", print_name: "Line-F" },
+    Op { name: "divu.w d1,d0", print_name: "DIVU by zero" },
+    Op { name: "divs.w d1,d0", print_name: "DIVS by zero" },
+    Op { name: "move.w #$2700,sr", print_name: "Privilege violation" },
+];
+
+// Exceptions of the 68020 forms of TRAPcc and CHK
+const CASES_020: &[Op] = &[
+    Op { name: "trapt", print_name: "TRAPcc, taken" },
+    Op { name: "trapt.w #0", print_name: "TRAPcc.W, taken" },
+    Op { name: "trapt.l #0", print_name: "TRAPcc.L, taken" },
+    Op { name: "chk.l d1,d0", print_name: "CHK.L, Dn < 0" },
+    Op { name: "chk.l d1,d0", print_name: "CHK.L, Dn > bound" },
+];
+
+const CYCLES: &[Op] = &[Op { name: "", print_name: "Cycles" }];
+
+// The default preset is supervisor mode with every flag clear and d0 = d1 = 1
+fn preset(case: &Op) -> Preset {
+    let mut preset = Preset::default();
+
+    match case.print_name {
+        "TRAPV, V set" => preset.sr |= 0x2,
+        "CHK, Dn < 0" => preset.d[0] = 0xffff,
+        "CHK, Dn > bound" | "CHK.L, Dn > bound" => preset.d[0] = 2,
+        "CHK.L, Dn < 0" => preset.d[0] = 0xffff_ffff,
+        "DIVU by zero" | "DIVS by zero" => preset.d[1] = 0,
+        "Privilege violation" => preset.sr = 0x0700,
+        _ => (),
+    }
+
+    preset
+}
+
+fn generate_table(context: &mut Context, cpu: Cpu, name: &str, cases: &'static [Op]) -> TimingTable<'static> {
+    let mut statements: Vec<BuildResult> = cases.iter().map(|case| {
+        BuildResult {
+            src: Some(*case),
+            dst: CYCLES[0],
+            statement: case.name.to_owned(),
+            code: Vec::new(),
+            preset: Some(preset(case)),
+            cycle_count: None,
+            bus: None,
+            fault: None,
+            trace: Vec::new(),
+            cases: None,
+            rejection: None,
+        }
+    }).collect();
+
+    assemble_statements(context, cpu, &mut statements);
+    compile_cycle_counts(context, name, &mut statements);
+
+    TimingTable {
+        name: name.to_owned(),
+        size: None,
+        layout: TableLayout::Grid(cases, CYCLES),
+        statements,
+        caption: None,
+    }
+}
+
+pub(crate) fn generate_chapter<'a>(context: &mut Context, cpu: Cpu) -> Chapter<'a> {
+    let mut tables = vec![Table::Measured(generate_table(context, cpu, "exception", CASES))];
+
+    if cpu >= Cpu::M68020 {
+        tables.push(Table::Measured(generate_table(context, cpu, "exception (68020)", CASES_020)));
+    }
+
+    Chapter {
+        title: "Exception Processing",
+        text: TEXT,
+        tables,
+    }
+}
@@ -0,0 +1,704 @@
+// Static HTML site generated from the same tables as the Markdown output.
+//
+// Layout of the output directory:
+//
+//   index.html                 links to the documentation set of each CPU
+//   <cpu>/                     one documentation set per CPU (68000, 68010, ...)
+//     index.html               alphabetical and categorized index with a search box
+//     <slug>.html              one page per instruction
+//     addressing_modes.html    anchor target for every addressing mode column
+//     chapter_<slug>.html      one page per chapter (exception processing, ...)
+//     search_index.js          client-side search data (plain script so it works from file://)
+//     search.js, style.css
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+use cpu::Cpu;
+use descriptions::*;
+use ea;
+use json;
+use operands;
+use sweep;
+use {check_affected, cycle_text, CASES_NOTE, get_flag_status, has_cycle_counts, SPLIT_NOTE};
+use {BuildResult, CellFormat, Category, Chapter, DocSet, Instruction, Op, Table, TableLayout, TimingTable};
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 72em; margin: 0 auto; padding: 1em; }
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: center; }
+td.rejected { color: #aaa; }
+td.mismatch { background: #fdd; }
+td.fault { color: #a00; }
+td.label, th.label { text-align: left; }
+td.trace { text-align: left; font-family: monospace; }
+p.anomaly { color: #a00; }
+nav a { margin-right: 1em; }
+ul.columns { columns: 4; list-style: none; padding: 0; }
+#search-results li { margin: 0.2em 0; }
+.operation { font-family: monospace; white-space: pre-wrap; }
+";
+
+const SEARCH_JS: &str = "function search(text) {
+    var results = document.getElementById('search-results');
+    results.innerHTML = '';
+    text = text.trim().toLowerCase();
+    if (text.length == 0) { return; }
+    SEARCH_INDEX.forEach(function(entry) {
+        var haystack = (entry.title + ' ' + entry.mnemonic + ' ' + entry.category + ' ' + entry.operation).toLowerCase();
+        if (haystack.indexOf(text) != -1) {
+            var li = document.createElement('li');
+            var a = document.createElement('a');
+            a.href = entry.page;
+            a.textContent = entry.title + ' \u{2014} ' + entry.operation;
+            li.appendChild(a);
+            results.appendChild(li);
+        }
+    });
+}
+";
+
+const ADDRESSING_MODES: &[(&str, &str)] = &[
+    ("Dn", "Data register direct"),
+    ("An", "Address register direct"),
+    ("(An)", "Address register indirect"),
+    ("(An)+", "Address register indirect with postincrement"),
+    ("-(An)", "Address register indirect with predecrement"),
+    ("d(An)", "Address register indirect with 16-bit displacement"),
+    ("d(An,Dn)", "Address register indirect with 8-bit displacement and index"),
+    ("xxx.W", "Absolute short"),
+    ("xxx.L", "Absolute long"),
+    ("d(PC)", "Program counter indirect with 16-bit displacement"),
+    ("d(PC,Dn)", "Program counter indirect with 8-bit displacement and index"),
+    ("#xxx", "Immediate"),
+    ("(d8,An,Dn.L*4)", "Address register indirect with 8-bit displacement and scaled index (68020)"),
+    ("(bd,An,Xn)", "Address register indirect with base displacement and index (68020)"),
+    ("([bd,An],Xn,od)", "Memory indirect postindexed (68020)"),
+    ("([bd,An,Xn],od)", "Memory indirect preindexed (68020)"),
+    ("(d8,PC,Dn.L*4)", "Program counter indirect with 8-bit displacement and scaled index (68020)"),
+    ("(bd,PC,Xn)", "Program counter indirect with base displacement and index (68020)"),
+    ("([bd,PC],Xn,od)", "Program counter memory indirect postindexed (68020)"),
+    ("([bd,PC,Xn],od)", "Program counter memory indirect preindexed (68020)"),
+    ("CCR", "Condition code register"),
+    ("SR", "Status register"),
+    ("USP", "User stack pointer"),
+    ("SFC", "Source function code register"),
+    ("DFC", "Destination function code register"),
+    ("VBR", "Vector base register"),
+    ("CACR", "Cache control register"),
+    ("CAAR", "Cache address register"),
+    ("MSP", "Master stack pointer"),
+    ("ISP", "Interrupt stack pointer"),
+];
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+// Lower case with everything but letters and digits turned into '_'
+pub fn slug(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+
+    out.trim_matches('_').to_owned()
+}
+
+fn page_name(inst: &Instruction) -> String {
+    format!("{}.html", slug(&inst.title()))
+}
+
+fn chapter_page_name(chapter: &Chapter) -> String {
+    format!("chapter_{}.html", slug(chapter.title))
+}
+
+// Slug of an addressing mode. The memory indirect modes only differ in where the bracket
+// closes, so that is kept
+fn mode_slug(mode: &str) -> String {
+    slug(&mode.replace("]", " i "))
+}
+
+// Operands that are addressing modes link to their description, others (branch paths, condition codes) are plain text
+fn mode_label(op: &Op) -> String {
+    if ADDRESSING_MODES.iter().any(|&(mode, _)| mode == op.print_name) {
+        format!("<a href=\"addressing_modes.html#ea_{}\">{}</a>", mode_slug(op.print_name), escape(op.print_name))
+    } else {
+        escape(op.print_name)
+    }
+}
+
+fn page_start(title: &str) -> String {
+    format!("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n\
+             <nav><a href=\"../index.html\">CPUs</a><a href=\"index.html\">Index</a>\
+             <a href=\"addressing_modes.html\">Addressing Modes</a></nav>\n",
+            escape(title))
+}
+
+fn page_end() -> &'static str {
+    "</body>\n</html>\n"
+}
+
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape(p).replace("\n", "<br>\n")))
+        .collect()
+}
+
+fn cell(result: &BuildResult, format: CellFormat) -> String {
+    match (cycle_text(result, format), result.fault, &result.rejection) {
+        (Some(text), _, _) => format!("<td>{}</td>", text),
+        (None, Some(fault), _) => format!("<td class=\"fault\" title=\"{}\">emulation fault</td>", escape(&fault.describe())),
+        (None, None, Some(rejection)) => {
+            format!("<td class=\"rejected\" title=\"{}: {}\">{}</td>", rejection.category.name(), escape(&rejection.message),
+                    escape(&format.rejected_symbols[rejection.category as usize].to_string()))
+        }
+        (None, None, None) => "<td class=\"rejected\">*</td>".to_owned(),
+    }
+}
+
+fn mode_header(table_id: &str, name: &str, dest_table: &[Op]) -> String {
+    let mut out = format!("<tr><th class=\"label\">{}</th>", escape(name));
+
+    for dst in dest_table {
+        out.push_str(&format!("<th id=\"{}_{}\">{}</th>", table_id, mode_slug(dst.print_name), mode_label(dst)));
+    }
+
+    out.push_str("</tr>\n");
+    out
+}
+
+fn timing_table(table: &TimingTable, format: CellFormat) -> String {
+    let id = slug(&table.name);
+    let mut out = format!("<table id=\"{}\">\n", id);
+
+    match table.layout {
+        TableLayout::Grid(src_table, dest_table) => {
+            out.push_str(&mode_header(&id, &table.name, dest_table));
+
+            for (src, row) in src_table.iter().zip(table.statements.chunks(dest_table.len())) {
+                if !has_cycle_counts(row) {
+                    continue;
+                }
+
+                out.push_str(&format!("<tr><th class=\"label\">{}</th>", mode_label(src)));
+
+                for result in row {
+                    out.push_str(&cell(result, format));
+                }
+
+                out.push_str("</tr>\n");
+            }
+        }
+
+        TableLayout::Row(dest_table) => {
+            out.push_str(&mode_header(&id, &table.name, dest_table));
+            out.push_str("<tr><td></td>");
+
+            for result in &table.statements {
+                out.push_str(&cell(result, format));
+            }
+
+            out.push_str("</tr>\n");
+        }
+
+        TableLayout::Single => {
+            out.push_str(&format!("<tr><th class=\"label\">{}</th>{}</tr>\n",
+                                  escape(&table.name), cell(&table.statements[0], format)));
+        }
+
+        TableLayout::Sweep(ref values) => {
+            let points = table.sweep_points();
+            let fit = sweep::fit(&points);
+            let mismatches = fit.map(|fit| sweep::mismatches(&fit, &points)).unwrap_or_default();
+
+            if let Some(fit) = fit {
+                out = format!("<p>{}: <code>{}</code></p>\n{}", escape(&table.name), fit.formula(), out);
+            }
+
+            for (i, (values, row)) in values.chunks(16).zip(table.statements.chunks(16)).enumerate() {
+                let label = if i == 0 { escape(&table.name) } else { "n".to_owned() };
+
+                out.push_str(&format!("<tr><th class=\"label\">{}</th>", label));
+
+                for n in values {
+                    out.push_str(&format!("<th>{}</th>", n));
+                }
+
+                out.push_str("</tr>\n<tr><th class=\"label\">cycles</th>");
+
+                for (n, result) in values.iter().zip(row) {
+                    if mismatches.iter().any(|m| m.0 == *n) {
+                        out.push_str(&format!("<td class=\"mismatch\">{}</td>", cycle_text(result, format).unwrap()));
+                    } else {
+                        out.push_str(&cell(result, format));
+                    }
+                }
+
+                out.push_str("</tr>\n");
+            }
+        }
+
+        // Rendered by operand_sweep() instead
+        TableLayout::Operands(..) => (),
+    }
+
+    out.push_str("</table>\n");
+
+    match table.caption {
+        Some(ref caption) => format!("<p>{}</p>\n{}", escape(caption), out),
+        None => out,
+    }
+}
+
+// Range, fitted formula and the cycles measured for every n of an operand sweep
+fn operand_sweep(table: &TimingTable, n_meaning: &str) -> String {
+    let measured = operands::measured(table);
+
+    let (summary, fit) = match (operands::summary(&measured), sweep::fit(&table.sweep_points())) {
+        (Some(summary), Some(fit)) => (summary, fit),
+        _ => return String::new(),
+    };
+
+    let mismatches = operands::mismatches(&fit, &measured);
+
+    let mut out = format!("<p>{}: {} operand values, min {}, max {}, average {:.1} cycles, \
+                           <code>{}</code> with n = {}</p>\n",
+                          escape(&table.name), measured.len(), summary.min, summary.max, summary.average,
+                          fit.formula(), escape(n_meaning));
+
+    out.push_str(&format!("<table id=\"{}\">\n<tr><th>n</th><th>values</th><th>cycles</th></tr>\n", slug(&table.name)));
+
+    for group in operands::groups(&measured) {
+        let class = if mismatches.iter().any(|m| m.0.n == group.n) { " class=\"mismatch\"" } else { "" };
+
+        out.push_str(&format!("<tr><td>{}</td><td>{}</td><td{}>{}</td></tr>\n",
+                              group.n, group.values, class, operands::group_cycles(&group)));
+    }
+
+    out.push_str("</table>\n");
+
+    if !mismatches.is_empty() {
+        out.push_str(&format!("<p>{} values don't match the formula, e.g. {}: measured {} cycles, formula gives {}</p>\n",
+                              mismatches.len(), operands::describe(&mismatches[0].0), mismatches[0].1,
+                              fit.cycles(mismatches[0].0.n)));
+    }
+
+    out
+}
+
+fn predef_table(name: &str, rows: &[&[&'static str]]) -> String {
+    let mut out = format!("<table id=\"{}\">\n<tr><th class=\"label\">{}</th>", slug(name), escape(name));
+
+    for entry in rows[0] {
+        out.push_str(&format!("<th>{}</th>", escape(entry)));
+    }
+
+    out.push_str("</tr>\n");
+
+    for row in &rows[1..] {
+        out.push_str("<tr>");
+
+        for entry in row.iter() {
+            out.push_str(&format!("<td>{}</td>", escape(entry)));
+        }
+
+        out.push_str("</tr>\n");
+    }
+
+    out.push_str("</table>\n");
+    out
+}
+
+fn cc_table(rows: &[&[&'static str]]) -> String {
+    let mut out = "<table>\n".to_owned();
+
+    for (i, row) in rows.iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+
+        out.push_str("<tr>");
+
+        for entry in row.iter() {
+            out.push_str(&format!("<{0}>{1}</{0}>", tag, escape(entry)));
+        }
+
+        out.push_str("</tr>\n");
+    }
+
+    out.push_str("</table>\n");
+    out
+}
+
+fn flag_text(name: &str, flag: &Flag) -> String {
+    match *flag {
+        Flag::Set(text) | Flag::Clear(text) | Flag::NotAffected(text) => escape(text),
+        Flag::Undefined => format!("{} — Undefined", name),
+    }
+}
+
+fn flags_section(flags: &FlagsDesc) -> String {
+    let mut out = "<h3>Condition Codes</h3>\n".to_owned();
+
+    if !check_affected(flags) {
+        out.push_str("<p>Not affected.</p>\n");
+        return out;
+    }
+
+    out.push_str("<table>\n<tr><th>X</th><th>N</th><th>Z</th><th>V</th><th>C</th></tr>\n<tr>");
+
+    for flag in &[&flags.x, &flags.n, &flags.z, &flags.v, &flags.c] {
+        out.push_str(&format!("<td>{}</td>", get_flag_status(flag)));
+    }
+
+    out.push_str("</tr>\n</table>\n<ul>\n");
+
+    for &(name, flag) in &[("X", &flags.x), ("N", &flags.n), ("Z", &flags.z), ("V", &flags.v), ("C", &flags.c)] {
+        out.push_str(&format!("<li>{}</li>\n", flag_text(name, flag)));
+    }
+
+    out.push_str("</ul>\n");
+    out
+}
+
+fn tables_html(tables: &[Table], format: CellFormat) -> String {
+    let mut out = String::new();
+
+    for table in tables {
+        match *table {
+            Table::Predefined(ref name, _, rows) => out.push_str(&predef_table(name, rows)),
+            Table::Measured(ref table) => {
+                if !has_cycle_counts(&table.statements) {
+                    continue;
+                }
+
+                match table.layout {
+                    TableLayout::Operands(_, n_meaning) => out.push_str(&operand_sweep(table, n_meaning)),
+                    _ => out.push_str(&timing_table(table, format)),
+                }
+            }
+        }
+    }
+
+    out
+}
+
+// Base + EA of every grid table over source addressing modes, anomalies are highlighted
+fn decompositions(tables: &[Table], ea_times: &ea::EaTimes) -> String {
+    let mut out = String::new();
+
+    for table in tables {
+        let (table, decomposition) = match *table {
+            Table::Measured(ref table) => match ea::decompose(table, ea_times) {
+                Some(decomposition) => (table, decomposition),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        out.push_str(&format!("<table id=\"{}_ea\">\n<tr><th class=\"label\">{}</th>", slug(&table.name), escape(&table.name)));
+
+        for &(dst, _) in &decomposition.bases {
+            out.push_str(&format!("<th>{}</th>", escape(dst)));
+        }
+
+        out.push_str("</tr>\n<tr><th class=\"label\">base + EA</th>");
+
+        for &(_, base) in &decomposition.bases {
+            out.push_str(&format!("<td>{}+EA</td>", base));
+        }
+
+        out.push_str("</tr>\n</table>\n");
+
+        for anomaly in &decomposition.anomalies {
+            out.push_str(&format!("<p class=\"anomaly\">Anomaly {},{}: measured {}, base + EA gives {}</p>\n",
+                                  escape(anomaly.src), escape(anomaly.dst), anomaly.measured, anomaly.expected));
+        }
+    }
+
+    if out.is_empty() {
+        out
+    } else {
+        format!("<h3>Base + Effective Address Time</h3>\n{}", out)
+    }
+}
+
+// Bus cycles of every traced statement in the order the instruction made them
+fn access_sequences(tables: &[Table]) -> String {
+    let mut out = String::new();
+
+    for table in tables {
+        if let Table::Measured(ref table) = *table {
+            if !table.has_access_trace() {
+                continue;
+            }
+
+            out.push_str(&format!("<table id=\"{}_trace\">\n<tr><th class=\"label\">{}</th><th>Access sequence</th></tr>\n",
+                                  slug(&table.name), escape(&table.name)));
+
+            for result in table.statements.iter().filter(|result| !result.trace.is_empty()) {
+                let accesses: Vec<String> = result.trace.iter().map(|access| escape(&access.describe())).collect();
+
+                out.push_str(&format!("<tr><td class=\"label\">{}</td><td class=\"trace\">{}</td></tr>\n",
+                                      escape(&result.statement), accesses.join("<br>")));
+            }
+
+            out.push_str("</table>\n");
+
+            if table.has_split_accesses() {
+                out.push_str(&format!("<p>{}</p>\n", SPLIT_NOTE));
+            }
+        }
+    }
+
+    if out.is_empty() {
+        out
+    } else {
+        format!("<h3>Access Sequences</h3>\n{}", out)
+    }
+}
+
+fn chapter_page(chapter: &Chapter, format: CellFormat, access_trace: bool) -> String {
+    let mut out = page_start(chapter.title);
+
+    out.push_str(&format!("<h1>{}</h1>\n", escape(chapter.title)));
+    out.push_str(&paragraphs(chapter.text));
+    out.push_str(&tables_html(&chapter.tables, format));
+
+    if access_trace {
+        out.push_str(&access_sequences(&chapter.tables));
+    }
+
+    out.push_str(page_end());
+    out
+}
+
+fn link(inst: &Instruction) -> String {
+    format!("<a href=\"{}\">{}</a>", page_name(inst), escape(&inst.title()))
+}
+
+// True if the cells give the 68020 best, cache and worst case
+fn has_cases(tables: &[Table]) -> bool {
+    tables.iter().any(|table| match *table {
+        Table::Measured(ref table) => table.statements.iter().any(|result| result.cases.is_some()),
+        _ => false,
+    })
+}
+
+fn instruction_page(docs: &[(&Instruction, Vec<Table>)], index: usize, ea_times: &ea::EaTimes,
+                    format: CellFormat, access_trace: bool) -> String {
+    let (inst, ref tables) = docs[index];
+    let title = inst.title();
+    let mut out = page_start(&title);
+
+    out.push_str(&format!("<h1>{}</h1>\n<p>Category: <a href=\"index.html#cat_{}\">{}</a></p>\n",
+                          escape(&title), slug(inst.category.name()), inst.category.name()));
+
+    if let Some(ref desc) = inst.desc {
+        out.push_str(&format!("<p><b>Operation:</b> <span class=\"operation\">{}</span></p>\n",
+                              escape(desc.operation.trim())));
+
+        out.push_str("<table>\n<tr><th class=\"label\">Assembler Syntax</th>");
+
+        for assem in desc.assembler {
+            out.push_str(&format!("<td>{}</td>", escape(assem)));
+        }
+
+        out.push_str("</tr>\n</table>\n");
+        out.push_str(&format!("<p><b>Attributes:</b> Size = ({})</p>\n", escape(desc.attributes)));
+        out.push_str("<h3>Description</h3>\n");
+        out.push_str(&paragraphs(desc.description));
+
+        if let Some(cc_codes) = inst.cc_codes {
+            out.push_str(&cc_table(cc_codes));
+        }
+
+        out.push_str(&flags_section(desc.flags));
+    } else {
+        out.push_str("<p><i>No Description</i></p>\n");
+    }
+
+    out.push_str("<h3>Instruction Execution Times</h3>\n");
+
+    if has_cases(tables) {
+        out.push_str(&format!("<p>{}</p>\n", CASES_NOTE));
+    }
+
+    out.push_str(&tables_html(tables, format));
+    out.push_str(&decompositions(tables, ea_times));
+
+    if access_trace {
+        out.push_str(&access_sequences(tables));
+    }
+
+    // Instructions sharing a description (ASL/ASR, DIVS/DIVU, ...) are the closest relatives
+    let related: Vec<String> = docs.iter()
+        .enumerate()
+        .filter(|&(i, &(other, _))| i != index && other.desc.is_some() && other.desc == inst.desc)
+        .map(|(_, &(other, _))| link(other))
+        .collect();
+
+    if !related.is_empty() {
+        out.push_str(&format!("<p>See also: {}</p>\n", related.join(", ")));
+    }
+
+    out.push_str("<nav>");
+
+    if index > 0 {
+        out.push_str(&format!("Previous: {} ", link(docs[index - 1].0)));
+    }
+
+    if index + 1 < docs.len() {
+        out.push_str(&format!("Next: {}", link(docs[index + 1].0)));
+    }
+
+    out.push_str("</nav>\n");
+    out.push_str(page_end());
+    out
+}
+
+fn index_page(cpu: Cpu, docs: &[(&Instruction, Vec<Table>)], chapters: &[Chapter]) -> String {
+    let title = format!("MC{} Instruction Timings", cpu.name());
+    let mut out = page_start(&title);
+
+    out.push_str(&format!("<h1>{}</h1>\n", title));
+    out.push_str("<p><input type=\"search\" placeholder=\"Search\" oninput=\"search(this.value)\"></p>\n");
+    out.push_str("<ul id=\"search-results\"></ul>\n");
+
+    let mut sorted: Vec<&Instruction> = docs.iter().map(|&(inst, _)| inst).collect();
+    sorted.sort_by_key(|inst| inst.title().to_lowercase());
+
+    out.push_str("<h2>Alphabetical</h2>\n<ul class=\"columns\">\n");
+
+    for inst in &sorted {
+        out.push_str(&format!("<li>{}</li>\n", link(inst)));
+    }
+
+    out.push_str("</ul>\n<h2>By Category</h2>\n");
+
+    for category in Category::all() {
+        let insts: Vec<&&Instruction> = sorted.iter().filter(|inst| inst.category == *category).collect();
+
+        if insts.is_empty() {
+            continue;
+        }
+
+        out.push_str(&format!("<h3 id=\"cat_{}\">{}</h3>\n<ul class=\"columns\">\n",
+                              slug(category.name()), category.name()));
+
+        for inst in insts {
+            out.push_str(&format!("<li>{}</li>\n", link(inst)));
+        }
+
+        out.push_str("</ul>\n");
+    }
+
+    if !chapters.is_empty() {
+        out.push_str("<h2>Chapters</h2>\n<ul>\n");
+
+        for chapter in chapters {
+            out.push_str(&format!("<li><a href=\"{}\">{}</a></li>\n", chapter_page_name(chapter), escape(chapter.title)));
+        }
+
+        out.push_str("</ul>\n");
+    }
+
+    out.push_str("<script src=\"search_index.js\"></script>\n<script src=\"search.js\"></script>\n");
+    out.push_str(page_end());
+    out
+}
+
+fn addressing_modes_page() -> String {
+    let mut out = page_start("Addressing Modes");
+
+    out.push_str("<h1>Addressing Modes</h1>\n<table>\n<tr><th>Mode</th><th>Description</th></tr>\n");
+
+    for &(mode, text) in ADDRESSING_MODES {
+        out.push_str(&format!("<tr id=\"ea_{}\"><td>{}</td><td class=\"label\">{}</td></tr>\n",
+                              mode_slug(mode), escape(mode), escape(text)));
+    }
+
+    out.push_str("</table>\n");
+    out.push_str(page_end());
+    out
+}
+
+fn search_index(docs: &[(&Instruction, Vec<Table>)]) -> String {
+    let entries: Vec<String> = docs.iter().map(|&(inst, _)| {
+        let operation = inst.desc.as_ref().map(|desc| desc.operation.trim()).unwrap_or("");
+
+        format!("  {{ \"title\": {}, \"mnemonic\": {}, \"category\": {}, \"operation\": {}, \"page\": {} }}",
+                json::escape(&inst.title()), json::escape(inst.name), json::escape(inst.category.name()),
+                json::escape(operation), json::escape(&page_name(inst)))
+    }).collect();
+
+    format!("var SEARCH_INDEX = [\n{}\n];\n", entries.join(",\n"))
+}
+
+fn write_file(dir: &Path, name: &str, contents: &str) -> io::Result<()> {
+    let mut file = File::create(dir.join(name))?;
+    file.write_all(contents.as_bytes())
+}
+
+fn cpu_index_page(sets: &[DocSet]) -> String {
+    let mut out = "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                   <title>Instruction Timings</title>\n</head>\n<body>\n<h1>Instruction Timings</h1>\n<ul>\n".to_owned();
+
+    for set in sets {
+        out.push_str(&format!("<li><a href=\"{}/index.html\">MC{}</a> ({} instructions)</li>\n",
+                              slug(set.cpu.name()), set.cpu.name(), set.instructions.len()));
+    }
+
+    out.push_str("</ul>\n");
+    out.push_str(page_end());
+    out
+}
+
+fn write_site(dir: &Path, set: &DocSet, format: CellFormat, access_trace: bool) -> io::Result<()> {
+    let docs = &set.instructions;
+
+    fs::create_dir_all(dir)?;
+
+    write_file(dir, "style.css", STYLE)?;
+    write_file(dir, "search.js", SEARCH_JS)?;
+    write_file(dir, "search_index.js", &search_index(docs))?;
+    write_file(dir, "index.html", &index_page(set.cpu, docs, &set.chapters))?;
+    write_file(dir, "addressing_modes.html", &addressing_modes_page())?;
+
+    for (i, &(inst, _)) in docs.iter().enumerate() {
+        write_file(dir, &page_name(inst), &instruction_page(docs, i, &set.ea_times, format, access_trace))?;
+    }
+
+    for chapter in &set.chapters {
+        write_file(dir, &chapter_page_name(chapter), &chapter_page(chapter, format, access_trace))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_sites(dir: &Path, sets: &[DocSet], format: CellFormat, access_trace: bool) -> io::Result<()> {
+    for set in sets {
+        write_site(&dir.join(slug(set.cpu.name())), set, format, access_trace)?;
+    }
+
+    write_file(dir, "index.html", &cpu_index_page(sets))
+}
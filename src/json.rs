@@ -0,0 +1,389 @@
+// JSON export of the instruction data set.
+//
+// Schema (version 1):
+//
+// {
+//   "schema_version": 1,
+//   "sets": [{
+//     "cpu": "68000",
+//     "instructions": [{
+//       "mnemonic": "add",
+//       "title": "ADD",
+//       "category": "Integer Arithmetic",
+//       "operation": "...", "description": "...", "attributes": "...",
+//       "assembler": ["..."],
+//       "sizes": ["b", "w", "l"],
+//       "flags": { "x": { "state": "set|clear|not_affected|undefined", "text": "..." }, ... },
+//       "timings": [{ "table": "add.w", "size": "w", "src": "Dn", "dst": "(An)",
+//                     "statement": "add.w d0,(a0)", "status": "measured|rejected|fault|not_measured",
+//                     "cycles": 12, "best": null, "worst": null, "reads": 2, "writes": 1, "fault": null,
+//                     "rejection": null, "accesses": [{ "address": 4096, "size": 2, "write": false, "fc": 6,
+//                                                       "split": false }, ...] }],
+//       "sweeps": [{ "table": "asl.w Dn,Dn", "size": "w", "formula": "6+2n", "mismatches": [],
+//                    "points": [{ "n": 0, "statement": "asl.w d0,d1", "status": "measured", "cycles": 6, ... }] }],
+//       "operand_sweeps": [{ "table": "mulu.w Dn,Dn", "size": "w", "n": "ones in the source",
+//                            "formula": "38+2n", "min": 38, "max": 70, "average": 54.0, "mismatches": 0,
+//                            "points": [[0, 1, 0, 38], ...] }],
+//       "predefined_tables": [{ "table": "bcc", "size": null, "rows": [["..."]] }],
+//       "ea_decomposition": [{ "table": "add.w", "bases": [{ "dst": "Dn", "base": 4 }],
+//                              "anomalies": [{ "src": "An", "dst": "Dn", "measured": 4, "expected": 8 }] }]
+//     }],
+//     "chapters": [{
+//       "title": "Exception Processing",
+//       "text": "...",
+//       "timings": [{ "table": "exception", "src": "TRAP #n", "dst": "Cycles", ... }]
+//     }]
+//   }]
+// }
+//
+// There is one set per documented CPU. Chapters hold the timings that aren't tied
+// to a single instruction, their timings use the same format as the instructions'.
+// Fields that don't apply (no description, no source operand, rejected cycles, no
+// formula) are null. Statements that were assembled but have no register state to run
+// them with (e.g. a BSR that isn't taken) have status "not_measured". Sweeps list the
+// timings measured over a range of n (e.g. shift counts) and the fitted formula.
+// reads/writes are the bus cycles of a measured instruction, including the opcode
+// and extension word fetches (a long takes two on the 68000/68010, one on the 68020).
+// Measurements the emulator aborted have status "fault" and a fault object with
+// the address, size (1, 2 or 4), write flag and PC of the offending access.
+// accesses lists the bus cycles of an instruction in the order the emulator made them,
+// with the function code (1 user data, 2 user program, 5 supervisor data, 6 supervisor
+// program). Longs split over two bus cycles are listed as two words with split true, in
+// ascending address order: the emulator doesn't model which half a real CPU moves first
+// (a 68000 writes the low word of move.l to -(An) first).
+// Operand sweeps run MULU/MULS/DIVU/DIVS over many register values, every point is
+// [d1 (source), d0 (destination), n, cycles] with null cycles if it wasn't measured.
+// ea_decomposition gives the base time of every destination of a grid table, a cell
+// takes base + the EA time of its source from the "Effective Address Calculation"
+// chapter. anomalies are the cells that don't.
+// best and worst are the estimated 68020 best and worst case, cycles is the measured
+// cache case there. They are null on the other CPUs and for operands without an estimate.
+// rejection says why a rejected statement wasn't assembled: { "category":
+// "illegal_ea|unsupported_size|syntax_error|tool_failure", "message": "..." } with the
+// assembler's error output as the message.
+
+use std::io::{self, Write};
+
+use descriptions::*;
+use ea;
+use operands;
+use sweep;
+use {BuildResult, Chapter, DocSet, Instruction, Table, TableLayout, TimingTable};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+
+    out.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+fn opt_string(text: Option<&str>) -> String {
+    match text {
+        Some(text) => escape(text),
+        None => "null".to_owned(),
+    }
+}
+
+fn opt_number(number: Option<usize>) -> String {
+    number.map_or("null".to_owned(), |number| number.to_string())
+}
+
+fn string_list(list: &[&str]) -> String {
+    let items: Vec<String> = list.iter().map(|s| escape(s)).collect();
+    format!("[{}]", items.join(", "))
+}
+
+pub fn size_name(size: Size) -> &'static str {
+    match size {
+        Size::Byte => "b",
+        Size::Word => "w",
+        Size::Long => "l",
+    }
+}
+
+fn opt_size(size: Option<Size>) -> String {
+    opt_string(size.map(size_name))
+}
+
+fn flag(flag: &Flag) -> String {
+    let (state, text) = match *flag {
+        Flag::Set(text) => ("set", Some(text)),
+        Flag::Clear(text) => ("clear", Some(text)),
+        Flag::NotAffected(text) => ("not_affected", Some(text)),
+        Flag::Undefined => ("undefined", None),
+    };
+
+    format!("{{ \"state\": {}, \"text\": {} }}", escape(state), opt_string(text))
+}
+
+fn flags(flags: &FlagsDesc) -> String {
+    format!("{{ \"x\": {}, \"n\": {}, \"z\": {}, \"v\": {}, \"c\": {} }}",
+            flag(&flags.x), flag(&flags.n), flag(&flags.z), flag(&flags.v), flag(&flags.c))
+}
+
+fn timings(table: &TimingTable, out: &mut Vec<String>) {
+    for result in &table.statements {
+        let dst = match table.layout {
+            TableLayout::Single => None,
+            _ => Some(result.dst.print_name),
+        };
+
+        out.push(format!("{{ \"table\": {}, \"size\": {}, \"src\": {}, \"dst\": {}, {} }}",
+                         escape(&table.name),
+                         opt_size(table.size),
+                         opt_string(result.src.map(|op| op.print_name)),
+                         opt_string(dst),
+                         result_fields(result)));
+    }
+}
+
+// statement, status, cycles, 68020 cases, bus cycles, fault, rejection and access trace of a single measurement
+fn result_fields(result: &BuildResult) -> String {
+    let (status, cycles) = match (result.cycle_count, result.fault) {
+        (Some(cycles), _) => ("measured", cycles.to_string()),
+        (None, Some(_)) => ("fault", "null".to_owned()),
+        (None, None) if result.rejection.is_some() => ("rejected", "null".to_owned()),
+        (None, None) => ("not_measured", "null".to_owned()),
+    };
+
+    let fault = match result.fault {
+        Some(fault) => format!("{{ \"address\": {}, \"size\": {}, \"write\": {}, \"pc\": {} }}",
+                               fault.address, fault.size, fault.write != 0, fault.pc),
+        None => "null".to_owned(),
+    };
+
+    let (reads, writes) = match result.bus {
+        Some(bus) => (bus.reads.to_string(), bus.writes.to_string()),
+        None => ("null".to_owned(), "null".to_owned()),
+    };
+
+    let (best, worst) = match result.cases {
+        Some(cases) => (opt_number(cases.best), opt_number(cases.worst)),
+        None => ("null".to_owned(), "null".to_owned()),
+    };
+
+    let rejection = match result.rejection {
+        Some(ref rejection) => format!("{{ \"category\": {}, \"message\": {} }}",
+                                       escape(rejection.category.key()), escape(&rejection.message)),
+        None => "null".to_owned(),
+    };
+
+    let accesses: Vec<String> = result.trace.iter().map(|access| {
+        format!("{{ \"address\": {}, \"size\": {}, \"write\": {}, \"fc\": {}, \"split\": {} }}",
+                access.address, access.size, access.write != 0, access.fc, access.split != 0)
+    }).collect();
+
+    format!("\"statement\": {}, \"status\": {}, \"cycles\": {}, \"best\": {}, \"worst\": {}, \"reads\": {}, \"writes\": {}, \
+             \"fault\": {}, \"rejection\": {}, \"accesses\": [{}]",
+            escape(&result.statement), escape(status), cycles, best, worst, reads, writes, fault, rejection, accesses.join(", "))
+}
+
+fn sweep(table: &TimingTable, values: &[u32]) -> String {
+    let points = table.sweep_points();
+    let fit = sweep::fit(&points);
+
+    let mismatches: Vec<String> = match fit {
+        Some(ref fit) => sweep::mismatches(fit, &points).iter().map(|m| m.0.to_string()).collect(),
+        None => Vec::new(),
+    };
+
+    let entries: Vec<String> = values.iter().zip(&table.statements).map(|(n, result)| {
+        format!("{{ \"n\": {}, {} }}", n, result_fields(result))
+    }).collect();
+
+    format!("{{ \"table\": {}, \"size\": {}, \"formula\": {}, \"mismatches\": [{}], \"points\": [{}] }}",
+            escape(&table.name),
+            opt_size(table.size),
+            opt_string(fit.map(|fit| fit.formula()).as_deref()),
+            mismatches.join(", "),
+            entries.join(", "))
+}
+
+fn operand_sweep(table: &TimingTable, n_meaning: &str) -> String {
+    let measured = operands::measured(table);
+    let summary = operands::summary(&measured);
+    let fit = sweep::fit(&table.sweep_points());
+
+    let mismatches = match fit {
+        Some(ref fit) => operands::mismatches(fit, &measured).len(),
+        None => 0,
+    };
+
+    let points: Vec<String> = match table.layout {
+        TableLayout::Operands(ref points, _) => points.iter().zip(&table.statements).map(|(point, result)| {
+            format!("[{}, {}, {}, {}]", point.src, point.dst, point.n,
+                    result.cycle_count.map(|c| c.to_string()).unwrap_or("null".to_owned()))
+        }).collect(),
+        _ => Vec::new(),
+    };
+
+    let (min, max, average) = match summary {
+        Some(summary) => (summary.min.to_string(), summary.max.to_string(), format!("{:.2}", summary.average)),
+        None => ("null".to_owned(), "null".to_owned(), "null".to_owned()),
+    };
+
+    format!("{{ \"table\": {}, \"size\": {}, \"n\": {}, \"formula\": {}, \"min\": {}, \"max\": {}, \"average\": {}, \
+             \"mismatches\": {}, \"points\": [{}] }}",
+            escape(&table.name),
+            opt_size(table.size),
+            escape(n_meaning),
+            opt_string(fit.map(|fit| fit.formula()).as_deref()),
+            min, max, average, mismatches,
+            points.join(", "))
+}
+
+fn predefined(name: &str, size: Option<Size>, rows: &[&[&'static str]]) -> String {
+    let rows: Vec<String> = rows.iter().map(|row| string_list(row)).collect();
+
+    format!("{{ \"table\": {}, \"size\": {}, \"rows\": [{}] }}",
+            escape(name), opt_size(size), rows.join(", "))
+}
+
+fn decomposition(table: &TimingTable, decomposition: &ea::Decomposition) -> String {
+    let bases: Vec<String> = decomposition.bases.iter()
+        .map(|&(dst, base)| format!("{{ \"dst\": {}, \"base\": {} }}", escape(dst), base))
+        .collect();
+
+    let anomalies: Vec<String> = decomposition.anomalies.iter()
+        .map(|a| format!("{{ \"src\": {}, \"dst\": {}, \"measured\": {}, \"expected\": {} }}",
+                         escape(a.src), escape(a.dst), a.measured, a.expected))
+        .collect();
+
+    format!("{{ \"table\": {}, \"bases\": [{}], \"anomalies\": [{}] }}",
+            escape(&table.name), bases.join(", "), anomalies.join(", "))
+}
+
+fn instruction<W: Write>(out: &mut W, inst: &Instruction, tables: &[Table], ea_times: &ea::EaTimes) -> io::Result<()> {
+    writeln!(out, "        {{")?;
+    writeln!(out, "          \"mnemonic\": {},", escape(inst.name))?;
+    writeln!(out, "          \"title\": {},", escape(&inst.title()))?;
+    writeln!(out, "          \"category\": {},", escape(inst.category.name()))?;
+
+    match inst.desc {
+        Some(ref desc) => {
+            let sizes: Vec<&str> = desc.sizes().into_iter().map(size_name).collect();
+
+            writeln!(out, "          \"operation\": {},", escape(desc.operation))?;
+            writeln!(out, "          \"description\": {},", escape(desc.description))?;
+            writeln!(out, "          \"attributes\": {},", escape(desc.attributes))?;
+            writeln!(out, "          \"assembler\": {},", string_list(desc.assembler))?;
+            writeln!(out, "          \"sizes\": {},", string_list(&sizes))?;
+            writeln!(out, "          \"flags\": {},", flags(desc.flags))?;
+        }
+
+        None => {
+            for field in &["operation", "description", "attributes", "assembler", "sizes", "flags"] {
+                writeln!(out, "          \"{}\": null,", field)?;
+            }
+        }
+    }
+
+    let mut cells = Vec::new();
+    let mut sweeps = Vec::new();
+    let mut operand_sweeps = Vec::new();
+    let mut predef = Vec::new();
+    let mut decompositions = Vec::new();
+
+    for table in tables {
+        if let Table::Measured(ref table) = *table {
+            if let Some(ref d) = ea::decompose(table, ea_times) {
+                decompositions.push(decomposition(table, d));
+            }
+        }
+
+        match *table {
+            Table::Measured(ref table) => match table.layout {
+                TableLayout::Sweep(ref values) => sweeps.push(sweep(table, values)),
+                TableLayout::Operands(_, n_meaning) => operand_sweeps.push(operand_sweep(table, n_meaning)),
+                _ => timings(table, &mut cells),
+            },
+            Table::Predefined(ref name, size, rows) => predef.push(predefined(name, size, rows)),
+        }
+    }
+
+    writeln!(out, "          \"timings\": [")?;
+    writeln!(out, "{}", cells.iter().map(|c| format!("            {}", c)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ],")?;
+    writeln!(out, "          \"sweeps\": [")?;
+    writeln!(out, "{}", sweeps.iter().map(|s| format!("            {}", s)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ],")?;
+    writeln!(out, "          \"operand_sweeps\": [")?;
+    writeln!(out, "{}", operand_sweeps.iter().map(|s| format!("            {}", s)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ],")?;
+    writeln!(out, "          \"predefined_tables\": [")?;
+    writeln!(out, "{}", predef.iter().map(|p| format!("            {}", p)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ],")?;
+    writeln!(out, "          \"ea_decomposition\": [")?;
+    writeln!(out, "{}", decompositions.iter().map(|d| format!("            {}", d)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ]")?;
+    write!(out, "        }}")
+}
+
+fn chapter<W: Write>(out: &mut W, chapter: &Chapter) -> io::Result<()> {
+    let mut cells = Vec::new();
+
+    for table in &chapter.tables {
+        if let Table::Measured(ref table) = *table {
+            timings(table, &mut cells);
+        }
+    }
+
+    writeln!(out, "        {{")?;
+    writeln!(out, "          \"title\": {},", escape(chapter.title))?;
+    writeln!(out, "          \"text\": {},", escape(chapter.text))?;
+    writeln!(out, "          \"timings\": [")?;
+    writeln!(out, "{}", cells.iter().map(|c| format!("            {}", c)).collect::<Vec<_>>().join(",\n"))?;
+    writeln!(out, "          ]")?;
+    write!(out, "        }}")
+}
+
+fn separator(index: usize, len: usize) -> &'static str {
+    if index + 1 < len { "," } else { "" }
+}
+
+pub(crate) fn write_documentation<W: Write>(out: &mut W, sets: &[DocSet]) -> io::Result<()> {
+    writeln!(out, "{{")?;
+    writeln!(out, "  \"schema_version\": {},", SCHEMA_VERSION)?;
+    writeln!(out, "  \"sets\": [")?;
+
+    for (i, set) in sets.iter().enumerate() {
+        writeln!(out, "    {{")?;
+        writeln!(out, "      \"cpu\": {},", escape(set.cpu.name()))?;
+        writeln!(out, "      \"instructions\": [")?;
+
+        for (j, &(inst, ref tables)) in set.instructions.iter().enumerate() {
+            instruction(out, inst, tables, &set.ea_times)?;
+            writeln!(out, "{}", separator(j, set.instructions.len()))?;
+        }
+
+        writeln!(out, "      ],")?;
+        writeln!(out, "      \"chapters\": [")?;
+
+        for (j, c) in set.chapters.iter().enumerate() {
+            chapter(out, c)?;
+            writeln!(out, "{}", separator(j, set.chapters.len()))?;
+        }
+
+        writeln!(out, "      ]")?;
+        writeln!(out, "    }}{}", separator(i, sets.len()))?;
+    }
+
+    writeln!(out, "  ]")?;
+    writeln!(out, "}}")
+}
@@ -1,37 +1,183 @@
-extern crate rayon;
-
-use rayon::prelude::*;
-
-use std::process::Command;
+use std::process;
 use std::fs::File;
-use std::io::Write;
 use std::os::raw;
-use std::io::Read;
-
+use std::path::Path;
+
+pub mod assembler;
+pub mod cache;
+pub mod cases;
+pub mod compare;
+pub mod cpu;
+pub mod exceptions;
 pub mod descriptions;
-
+pub mod diagnostics;
+pub mod dialect;
+pub mod ea;
+pub mod encoder;
+pub mod html;
+pub mod json;
+pub mod operands;
+pub mod options;
+pub mod sweep;
+
+use cpu::Cpu;
 use descriptions::*;
+use options::{Options, OutputFormat};
+
+
+// Register state loaded before an instruction is run. Matches m68k_preset in m68k.h.
+// Address registers point into the NOP landing area of the wrapper's memory map, A7 to
+// the top of it where the supervisor stack starts (STACK_TOP), so exception frames are
+// pushed well above the NOPs the other address registers read
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct Preset {
+    d: [u32; 8],
+    a: [u32; 8],
+    sr: u32,
+}
+
+const DEFAULT_PRESET: Preset = Preset {
+    d: [1; 8],
+    a: [0x800, 0x800, 0x800, 0x800, 0x800, 0x800, 0x800, 0x1000],
+    sr: 0x2700,
+};
+
+impl Default for Preset {
+    fn default() -> Self {
+        DEFAULT_PRESET
+    }
+}
+
+// Register state an instruction is measured with, each one gets its own table named
+// after the label (e.g. best and worst case). Only registers are preset, memory
+// operands read the NOP landing area
+#[derive(Copy, Clone)]
+struct LabelledPreset {
+    label: &'static str,
+    preset: Preset,
+}
+
+// Extra operand matrix measured on the CPUs that have its addressing modes, in a
+// table of its own with the label appended to the name
+#[derive(Copy, Clone)]
+struct CpuMatrix<'a> {
+    min_cpu: Cpu,
+    label: &'static str,
+    matrix: &'a [&'a [Op]],
+}
+
+// Memory access outside of the RAM that aborted an instruction. Matches m68k_fault in m68k.h
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct Fault {
+    faulted: u32,
+    address: u32,
+    size: u32,
+    write: u32,
+    pc: u32,
+}
+
+impl Fault {
+    fn describe(&self) -> String {
+        let size = match self.size {
+            1 => "byte",
+            2 => "word",
+            _ => "long",
+        };
+
+        format!("{} {} at ${:08x} (PC ${:08x})",
+                if self.write != 0 { "write" } else { "read" }, size, self.address, self.pc)
+    }
+}
+
+// Read and write bus cycles of an instruction. Matches m68k_bus_count in m68k.h
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct BusCount {
+    reads: u32,
+    writes: u32,
+}
 
+// One bus cycle of an instruction's access trace. Matches m68k_access in m68k.h
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+struct Access {
+    address: u32,
+    size: u32,
+    write: u32,
+    fc: u32,
+    // Set on both word cycles of a long on the 16 bit bus, the order of the two isn't modeled
+    split: u32,
+}
 
-#[cfg(target_os="windows")]
-const VASM_EXE: &'static str = "bin/win/vasmm68k_mot.exe";
+impl Access {
+    fn fc_name(&self) -> &'static str {
+        match self.fc {
+            1 => "UD",
+            2 => "UP",
+            5 => "SD",
+            6 => "SP",
+            7 => "CPU",
+            _ => "?",
+        }
+    }
 
-#[cfg(target_os="macos")]
-const VASM_EXE: &'static str = "bin/mac/vasmm68k_mot";
+    // Direction, size, address and function code, e.g. W.w $000007fc SD, with a ~ after the
+    // halves of a split long, see SPLIT_NOTE
+    fn describe(&self) -> String {
+        let size = match self.size {
+            1 => "b",
+            2 => "w",
+            _ => "l",
+        };
+
+        format!("{}.{} ${:08x} {}{}", if self.write != 0 { "W" } else { "R" }, size, self.address, self.fc_name(),
+                if self.split != 0 { " ~" } else { "" })
+    }
+}
 
-#[cfg(any(target_os="linux",
-          target_os="freebsd",
-          target_os="dragonfly",
-          target_os="netbsd",
-          target_os="openbsd"))]
-const VASM_EXE: &'static str = "vasmm68k_mot";
+// Room in the access trace for a single instruction, MOVEM.L of 16 registers needs about 40
+const TRACE_ENTRIES_PER_INSTRUCTION: usize = 64;
+
+// Legend of the cells of CPUs with an instruction cache
+const CASES_NOTE: &str = "Cells give the best, cache and worst case, e.g. 3,5,6. The cache case is \
+                          measured, best and worst case are estimated from the fetch time of the source \
+                          operand and shown as - where that isn't known.";
+
+// Legend of the access sequences of tables with longs split over the 16 bit bus
+const SPLIT_NOTE: &str = "Accesses marked ~ are the two word cycles of a long. They are listed in ascending \
+                          address order, the emulator doesn't model which one a real CPU makes first.";
+
+// How measured and rejected cells are rendered
+#[derive(Copy, Clone, PartialEq)]
+struct CellFormat {
+    // Total clocks and read/write bus cycles in Motorola's notation, e.g. 12(2/1),
+    // instead of the total clocks only
+    bus_cycles: bool,
+    // Text of a rejected cell per rejection category
+    rejected_symbols: [char; 4],
+}
 
 struct BuildResult {
     src: Option<Op>,
     dst: Op,
-    temp_file: String,
-    temp_out: String,
+    statement: String,
+    // Assembled statement, empty if the assembler rejected it
+    code: Vec<u8>,
+    // None if no register state gives the combination (e.g. a BSR that isn't taken)
+    preset: Option<Preset>,
     cycle_count: Option<usize>,
+    // Bus cycles of a measured instruction
+    bus: Option<BusCount>,
+    // Set if the emulator aborted the instruction, cycle_count is None then
+    fault: Option<Fault>,
+    // Bus cycles of a measured or faulted instruction in the order they were made
+    trace: Vec<Access>,
+    // 68020 best, cache and worst case of a measured instruction
+    cases: Option<cases::Cases>,
+    // Why the assembler rejected the statement
+    rejection: Option<assembler::Rejection>,
 }
 
 #[derive(Copy, Clone)]
@@ -49,10 +195,74 @@ impl Op {
     }
 }
 
+// How the timings of an instruction are measured
+#[derive(Copy, Clone, PartialEq)]
+enum Measure {
+    // Every operand combination in the matrix
+    Matrix,
+    // Bcc/BRA/BSR: matrix is [mnemonics, [taken, not taken]], the CCR is preset for each column
+    Branch,
+    // DBcc: matrix is [mnemonics, [taken, not taken, expired]], CCR and counter are preset for each column
+    DecrementBranch,
+    // Shift/rotate: register forms swept over the shift count, matrix is [memory destinations] for the .w ea form
+    Shift,
+    // MOVEM: swept over 1-16 registers, matrix is [memory to register sources, register to memory destinations]
+    RegisterList,
+    // MULU/MULS/DIVU/DIVS: the matrix plus a sweep over register operand values
+    Operands,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Category {
+    DataMovement,
+    IntegerArithmetic,
+    Logical,
+    ShiftRotate,
+    BitManipulation,
+    Bcd,
+    ProgramControl,
+    SystemControl,
+    Multiprocessor,
+    Other,
+}
+
+impl Category {
+    pub fn all() -> &'static [Category] {
+        &[Category::DataMovement, Category::IntegerArithmetic, Category::Logical,
+          Category::ShiftRotate, Category::BitManipulation, Category::Bcd,
+          Category::ProgramControl, Category::SystemControl, Category::Multiprocessor,
+          Category::Other]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Category::DataMovement => "Data Movement",
+            Category::IntegerArithmetic => "Integer Arithmetic",
+            Category::Logical => "Logical",
+            Category::ShiftRotate => "Shift and Rotate",
+            Category::BitManipulation => "Bit Manipulation",
+            Category::Bcd => "Binary-Coded Decimal",
+            Category::ProgramControl => "Program Control",
+            Category::SystemControl => "System Control",
+            Category::Multiprocessor => "Multiprocessor",
+            Category::Other => "Other",
+        }
+    }
+}
+
 struct Instruction<'a> {
     name: &'static str,
+    title: Option<&'static str>,
     desc: Option<Description>,
+    category: Category,
+    // First CPU that has the instruction
+    min_cpu: Cpu,
+    measure: Measure,
+    // Register presets of Matrix measurements, the default preset if empty
+    presets: &'a [LabelledPreset],
     matrix: Option<&'a [&'a [Op]]>,
+    // Matrices of addressing modes later CPUs added
+    cpu_matrices: &'a [CpuMatrix<'a>],
     cc_codes: Option<&'a [&'a [&'static str]]>,
     override_output_b: Option<&'a [&'a [&'static str]]>,
     override_output_w: Option<&'a [&'a [&'static str]]>,
@@ -65,14 +275,123 @@ impl <'a> Instruction<'a> {
                self.override_output_w.is_some() ||
                self.override_output_l.is_some();
     }
+
+    pub fn title(&self) -> String {
+        match self.title {
+            Some(title) => title.to_owned(),
+            None => self.name.to_uppercase(),
+        }
+    }
+
+    // Table name suffix and register state of every Matrix measurement
+    pub fn labelled_presets(&self) -> Vec<(Option<&'static str>, Preset)> {
+        if self.presets.is_empty() {
+            vec![(None, Preset::default())]
+        } else {
+            self.presets.iter().map(|p| (Some(p.label), p.preset)).collect()
+        }
+    }
+
+    // Label and operands of every matrix measured on the CPU, the plain matrix comes first
+    pub fn matrices(&self, cpu: Cpu) -> Vec<(Option<&'static str>, &'a [&'a [Op]])> {
+        let mut matrices = vec![(None, self.matrix.unwrap())];
+
+        for matrix in self.cpu_matrices.iter().filter(|m| m.min_cpu <= cpu) {
+            matrices.push((Some(matrix.label), matrix.matrix));
+        }
+
+        matrices
+    }
+
+    pub fn sizes(&self) -> Vec<Size> {
+        match self.desc {
+            Some(ref desc) => desc.sizes(),
+            None => Vec::new(),
+        }
+    }
+}
+
+enum TableLayout<'a> {
+    Grid(&'a [Op], &'a [Op]),
+    Row(&'a [Op]),
+    Single,
+    // One statement per value of n
+    Sweep(Vec<u32>),
+    // One statement per operand value and what n counts in the fitted formula
+    Operands(Vec<operands::Point>, &'static str),
+}
+
+struct TimingTable<'a> {
+    name: String,
+    size: Option<Size>,
+    layout: TableLayout<'a>,
+    statements: Vec<BuildResult>,
+    // Printed above the table, e.g. the operand values of data dependent timings
+    caption: Option<String>,
+}
+
+impl <'a> TimingTable<'a> {
+    // True if the access sequences of the table are worth listing
+    fn has_access_trace(&self) -> bool {
+        match self.layout {
+            TableLayout::Operands(..) => false,
+            _ => self.statements.iter().any(|result| !result.trace.is_empty()),
+        }
+    }
+
+    // True if the access sequences need SPLIT_NOTE
+    fn has_split_accesses(&self) -> bool {
+        self.statements.iter().any(|result| result.trace.iter().any(|access| access.split != 0))
+    }
+
+    // (n, cycles) of every measured statement in a sweep
+    fn sweep_points(&self) -> Vec<(u32, usize)> {
+        match self.layout {
+            TableLayout::Sweep(ref values) => {
+                values.iter()
+                    .zip(&self.statements)
+                    .filter_map(|(&n, result)| result.cycle_count.map(|cycles| (n, cycles)))
+                    .collect()
+            }
+            TableLayout::Operands(..) => operands::measured(self).iter().map(|m| (m.0.n, m.1)).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Timings that aren't tied to a single instruction (exception processing, ...)
+struct Chapter<'a> {
+    title: &'static str,
+    text: &'static str,
+    tables: Vec<Table<'a>>,
+}
+
+// Everything documented for one CPU
+struct DocSet<'a, 'b> {
+    cpu: Cpu,
+    instructions: Vec<(&'b Instruction<'a>, Vec<Table<'a>>)>,
+    chapters: Vec<Chapter<'a>>,
+    // EA calculation times derived from the instructions' tables
+    ea_times: ea::EaTimes,
+}
+
+enum Table<'a> {
+    Predefined(String, Option<Size>, &'a [&'a [&'static str]]),
+    Measured(TimingTable<'a>),
 }
 
 impl <'a> Default for Instruction <'a> {
     fn default() -> Self {
         Instruction {
             name: "",
+            title: None,
             desc: None,
+            category: Category::Other,
+            min_cpu: Cpu::M68000,
+            measure: Measure::Matrix,
+            presets: &[],
             matrix: None,
+            cpu_matrices: &[],
             cc_codes: None,
             override_output_b: None,
             override_output_w: None,
@@ -81,111 +400,109 @@ impl <'a> Default for Instruction <'a> {
     }
 }
 
-fn compile_statement(filename: &str, file_out: &str, statement: &str) -> bool {
-    {
-        let mut file = File::create(filename).unwrap();
-        write!(file, " {}", statement).unwrap();
-    }
+fn has_cycle_counts(cycles: &[BuildResult]) -> bool {
+    cycles.iter().any(|c| c.cycle_count.is_some() || c.fault.is_some())
+}
 
-    let output = Command::new(VASM_EXE)
-        .arg("-no-opt")
-        .arg("-m68000")
-        .arg(filename)
-        .arg("-Fbin")
-        .arg("-o")
-        .arg(file_out)
-        .output()
-        .expect("failed to execute process");
+fn print_table_header(name: &str, dest_table: &[Op]) {
+    print!("| {name:<width$}", name = name, width = 9);
 
-    output.status.success()
-}
+    for dst in dest_table {
+        print!("| {} ", dst.print_name);
+    }
 
-fn print_grid_table(name: &str, cycles: &Vec<BuildResult>, src_table: &[Op], dest_table: &[Op]) {
-    // Check if we have anything to print
+    println!("|");
+    print!("|----------");
 
-    let mut should_print = false;
+    for dst in dest_table {
+        print!("|");
+        fill_table_space(dst.print_name, 2);
+    }
 
-    for c in cycles {
-        if c.cycle_count.is_some() {
-            should_print = true;
-            break;
-        }
+    println!("|");
+}
+
+// Appends the read/write bus cycles to the text of a cell if they are shown
+fn with_bus_cycles(text: String, result: &BuildResult, format: CellFormat) -> String {
+    match result.bus {
+        Some(bus) if format.bus_cycles => format!("{}({}/{})", text, bus.reads, bus.writes),
+        _ => text,
     }
+}
 
-    if !should_print {
-        return;
+// Text of a measured cell, None if it wasn't measured
+fn cycle_text(result: &BuildResult, format: CellFormat) -> Option<String> {
+    match result.cases {
+        Some(ref cases) => Some(with_bus_cycles(cases::describe(cases), result, format)),
+        None => measured_text(result, format),
     }
+}
 
-    print!("| {name:<width$}", name = name, width = 9);
+// Text of the measured (cache case on the 68020) cycles of a cell, None if it wasn't measured
+fn measured_text(result: &BuildResult, format: CellFormat) -> Option<String> {
+    result.cycle_count.map(|count| with_bus_cycles(count.to_string(), result, format))
+}
 
-    for dst in dest_table {
-        print!("| {} ", dst.print_name);
-    }
+// Symbol of a cell the assembler rejected, None if it wasn't rejected
+fn rejected_text(result: &BuildResult, format: CellFormat) -> Option<String> {
+    result.rejection.as_ref().map(|rejection| format.rejected_symbols[rejection.category as usize].to_string())
+}
 
-    println!("|");
-    println!("|----------|----|----|------|-------|-------|-------|----------|-------|-------|");
+// Text of every cell in a table, None for combinations that weren't measured
+fn cycle_cells(cycles: &[BuildResult], format: CellFormat) -> Vec<Option<String>> {
+    cycles.iter()
+        .map(|c| match c.fault {
+            Some(_) => Some("emulation fault".to_owned()),
+            None => cycle_text(c, format).or(rejected_text(c, format)),
+        })
+        .collect()
+}
+
+fn print_cell(cell: &Option<String>, dest: &Op) {
+    print!("|{text:^width$}",
+           text = cell.as_ref().map(|c| c.as_str()).unwrap_or("*"),
+           width = dest.print_name.len() + 2);
+}
+
+fn print_grid_table(name: &str, cells: &[Option<String>], src_table: &[Op], dest_table: &[Op]) {
+    print_table_header(name, dest_table);
 
     let mut index = 0;
+    let row_len = dest_table.len();
 
     for src in src_table {
         let mut skip_count = 0;
 
-        for i in index..index + 9 {
-            if let None = cycles[i].cycle_count {
+        for cell in &cells[index..index + row_len] {
+            if cell.is_none() {
                 skip_count += 1;
             }
         }
 
-        if skip_count != 9 {
+        if skip_count != row_len {
             print!("| {name:<width$}", name = src.print_name, width = 9);
 
             for dest in dest_table {
-                if let Some(cycle_count) = cycles[index].cycle_count {
-                    print!("|{number:^width$}",
-                           number = cycle_count,
-                           width = dest.print_name.len() + 2);
-                } else {
-                    print!("|{number:^width$}",
-                           number = "*",
-                           width = dest.print_name.len() + 2);
-                }
+                print_cell(&cells[index], dest);
                 index += 1;
             }
 
             println!("|");
         } else {
-            index += 9;
+            index += row_len;
         }
     }
 
     println!("");
 }
 
-fn print_table(name: &str, cycles: &Vec<BuildResult>, dest_table: &[Op]) {
-    print!("| {name:<width$}", name = name, width = 9);
-
-    for dst in dest_table {
-        print!("| {} ", dst.print_name);
-    }
-
-    println!("|");
-    println!("|----------|----|----|------|-------|-------|-------|----------|-------|-------|");
-
-    let mut index = 0;
+fn print_table(name: &str, cells: &[Option<String>], dest_table: &[Op]) {
+    print_table_header(name, dest_table);
 
     print!("| {name:<width$}", name = " ", width = 9);
 
-    for dest in dest_table {
-        if let Some(cycle_count) = cycles[index].cycle_count {
-            print!("|{number:^width$}",
-                   number = cycle_count,
-                   width = dest.print_name.len() + 2);
-        } else {
-            print!("|{number:^width$}",
-                   number = "*",
-                   width = dest.print_name.len() + 2);
-        }
-        index += 1;
+    for (cell, dest) in cells.iter().zip(dest_table) {
+        print_cell(cell, dest);
     }
 
     println!("|");
@@ -281,22 +598,7 @@ fn print_condition_codes(table: &[&[&'static str]]) {
 
 
 fn print_instruction_header(inst: &Instruction) {
-    let mut name = inst.name.to_uppercase();
-
-    // Hack
-    if name == "BCC" {
-        name = "Bcc".to_owned();
-    }
-
-    if name == "SCC" {
-        name = "Scc".to_owned();
-    }
-
-    if name == "DBCC" {
-        name = "DBcc".to_owned();
-    }
-
-    println!("## {}\n", name);
+    println!("## {}\n", inst.title());
     if let Some(ref desc) = inst.desc {
         println!("**Operation:**      {}\n", desc.operation);
 
@@ -339,120 +641,390 @@ fn print_instruction_header(inst: &Instruction) {
     }
 }
 
-fn compile_cycle_counts(statements: &mut Vec<BuildResult>) {
+// Assembler, cache and rejected statements of a run, every table is assembled and measured
+// through it
+struct Context {
+    assembler: assembler::Selection,
+    cache: Option<cache::Cache>,
+    diagnostics: diagnostics::Diagnostics,
+}
+
+// Statements run per emulator call, long sweeps are split so their code fits in the smallest RAM
+const MAX_RUN_STATEMENTS: usize = 1024;
+
+fn compile_cycle_counts(context: &mut Context, table: &str, statements: &mut [BuildResult]) {
+    for chunk in statements.chunks_mut(MAX_RUN_STATEMENTS) {
+        match context.cache {
+            Some(ref mut cache) => cache.measure(table, chunk, |chunk| run_statements(table, chunk)),
+            None => run_statements(table, chunk),
+        }
+    }
+}
+
+fn run_statements(table: &str, statements: &mut [BuildResult]) {
     let mut instructions = Vec::new();
     let mut inst_count = 0u32;
+    let mut offsets = Vec::<u32>::new();
+    let mut presets = Vec::<Preset>::new();
     let mut cycle_count = Vec::<u32>::new();
+    let mut bus = Vec::<BusCount>::new();
+    let mut faults = Vec::<Fault>::new();
 
     for statement in statements.iter() {
         if statement.cycle_count.is_some() {
-            {
-                let mut f = File::open(&statement.temp_out).unwrap();
-                f.read_to_end(&mut instructions).unwrap();
-                cycle_count.push(0);
-                inst_count += 1;
-            }
+            offsets.push(instructions.len() as u32);
+            presets.push(statement.preset.unwrap());
+
+            instructions.extend_from_slice(&statement.code);
+            cycle_count.push(0);
+            bus.push(BusCount::default());
+            faults.push(Fault::default());
+            inst_count += 1;
         }
     }
 
+    let mut trace = vec![Access::default(); inst_count as usize * TRACE_ENTRIES_PER_INSTRUCTION];
+
     unsafe {
-        m68k_run_instructions(instructions.as_ptr() as *const raw::c_void, inst_count, cycle_count.as_mut_ptr());
+        m68k_run_instructions(instructions.as_ptr() as *const raw::c_void,
+                              instructions.len() as u32,
+                              inst_count,
+                              offsets.as_ptr(),
+                              presets.as_ptr(),
+                              cycle_count.as_mut_ptr(),
+                              bus.as_mut_ptr(),
+                              faults.as_mut_ptr(),
+                              trace.as_mut_ptr(),
+                              trace.len() as u32);
     }
 
     inst_count = 0;
 
+    // Every bus cycle is logged so each instruction owns the next reads + writes entries
+    let mut trace_start = 0;
+
     for statement in statements.iter_mut() {
         if statement.cycle_count.is_some() {
-            statement.cycle_count = Some(cycle_count[inst_count as usize] as usize);
+            let fault = faults[inst_count as usize];
+            let counts = bus[inst_count as usize];
+            let trace_end = trace_start + (counts.reads + counts.writes) as usize;
+
+            statement.trace = trace[trace_start.min(trace.len())..trace_end.min(trace.len())].to_vec();
+            trace_start = trace_end;
+
+            if fault.faulted != 0 {
+                eprintln!("warning: emulation fault in table {} running '{}': {}",
+                          table, statement.statement, fault.describe());
+                statement.cycle_count = None;
+                statement.fault = Some(fault);
+            } else {
+                statement.cycle_count = Some(cycle_count[inst_count as usize] as usize);
+                statement.bus = Some(bus[inst_count as usize]);
+            }
+
             inst_count += 1;
         }
     }
 }
 
-fn generate_statements_two_args(name: &str, inst: &Instruction) -> Vec<BuildResult> {
-    let mut statements = Vec::with_capacity(20 * 20);
-    let mut count = 0;
+// Assembles the statements that have a preset in one batch
+fn assemble_statements(context: &mut Context, cpu: Cpu, statements: &mut [BuildResult]) {
+    let sources: Vec<&str> = statements.iter()
+        .filter(|v| v.preset.is_some())
+        .map(|v| v.statement.as_str())
+        .collect();
+
+    let mut code = context.assembler
+        .assemble(cpu, &sources, context.cache.as_mut(), &mut context.diagnostics)
+        .into_iter();
+
+    for v in statements.iter_mut().filter(|v| v.preset.is_some()) {
+        match code.next().unwrap() {
+            Ok(bytes) => {
+                v.code = bytes;
+                v.cycle_count = Some(0); // indicate that this should be processed
+            }
+            Err(rejection) => v.rejection = Some(rejection),
+        }
+    }
+}
 
-    let matrix = inst.matrix.unwrap();
+fn generate_statements_two_args(context: &mut Context, cpu: Cpu, name: &str, matrix: &[&[Op]],
+                                preset: Preset) -> Vec<BuildResult> {
+    let mut statements = Vec::with_capacity(20 * 20);
 
     for src in matrix[0] {
         for dst in matrix[1] {
-            let file_in = format!("target/temp_{}.s", count);
-            let file_out = format!("target/temp_{}.o", count);
-
             statements.push(BuildResult {
                 src: Some(src.clone()),
                 dst: dst.clone(),
-                temp_file: file_in,
-                temp_out: file_out,
+                statement: format!("{} {},{}", name, src.name, dst.name),
+                code: Vec::new(),
+                preset: Some(preset),
                 cycle_count: None,
+                bus: None,
+                fault: None,
+                trace: Vec::new(),
+                cases: None,
+                rejection: None,
             });
-
-            count += 1;
         }
     }
 
-    statements.par_iter_mut().weight_max().for_each(|v| {
-        let statement = format!("{} {},{}", name, v.src.unwrap().name, v.dst.name);
-        if compile_statement(&v.temp_file, &v.temp_out, &statement) {
-            v.cycle_count = Some(0); // indicate that this should be processed
-        }
-    });
+    assemble_statements(context, cpu, &mut statements);
 
     statements
 }
 
-fn generate_statements_one_arg(name: &str, inst: &Instruction) -> Vec<BuildResult> {
+fn generate_statements_one_arg(context: &mut Context, cpu: Cpu, name: &str, matrix: &[&[Op]],
+                               preset: Preset) -> Vec<BuildResult> {
     let mut statements = Vec::with_capacity(20 * 20);
-    let mut count = 0;
-
-    let matrix = inst.matrix.unwrap();
 
     for dst in matrix[0] {
-        let file_in = format!("target/temp_{}.s", count);
-        let file_out = format!("target/temp_{}.o", count);
-
         statements.push(BuildResult {
             src: None,
             dst: dst.clone(),
-            temp_file: file_in,
-            temp_out: file_out,
+            statement: format!("{} {}", name, dst.name),
+            code: Vec::new(),
+            preset: Some(preset),
             cycle_count: None,
+            bus: None,
+            fault: None,
+            trace: Vec::new(),
+            cases: None,
+            rejection: None,
         });
-
-        count += 1;
     }
 
-    statements.par_iter_mut().weight_max().for_each(|v| {
-        let statement = format!("{} {}", name, v.dst.name);
-        if compile_statement(&v.temp_file, &v.temp_out, &statement) {
-            v.cycle_count = Some(0);
-        }
-    });
+    assemble_statements(context, cpu, &mut statements);
 
     statements
 }
 
-fn generate_statements_no_args(name: &str) -> Vec<BuildResult> {
+fn generate_statements_no_args(context: &mut Context, cpu: Cpu, name: &str, preset: Preset) -> Vec<BuildResult> {
     let mut statement = Vec::with_capacity(1);
-    let file_in = "target/temp_on_op.s";
-    let file_out = "target/temp_one_op.o";
 
     statement.push(BuildResult {
         src: None,
         dst: Op::new("", ""),
-        temp_file: file_in.to_owned(),
-        temp_out:  file_out.to_owned(),
+        statement: name.to_owned(),
+        code: Vec::new(),
+        preset: Some(preset),
         cycle_count: None,
+        bus: None,
+        fault: None,
+        trace: Vec::new(),
+        cases: None,
+        rejection: None,
     });
 
-    if compile_statement(&file_in, &file_out, name) {
-        statement[0].cycle_count = Some(0);
-    } 
+    assemble_statements(context, cpu, &mut statement);
 
     statement
 }
 
+// Evaluates the condition of a Bcc/DBcc mnemonic for the given CCR
+fn condition_holds(mnemonic: &str, ccr: u32) -> bool {
+    let c = ccr & 1 != 0;
+    let v = ccr & 2 != 0;
+    let z = ccr & 4 != 0;
+    let n = ccr & 8 != 0;
+
+    let cc = mnemonic.strip_prefix("db").unwrap_or(&mnemonic[1..]);
+
+    match cc {
+        "t" | "ra" | "sr" => true,
+        "f" => false,
+        "hi" => !c && !z,
+        "ls" => c || z,
+        "cc" => !c,
+        "cs" => c,
+        "ne" => !z,
+        "eq" => z,
+        "vc" => !v,
+        "vs" => v,
+        "pl" => !n,
+        "mi" => n,
+        "ge" => n == v,
+        "lt" => n != v,
+        "gt" => n == v && !z,
+        "le" => z || n != v,
+        _ => panic!("unknown condition in {}", mnemonic),
+    }
+}
+
+// Register state that sends a branch down the path of the given column
+fn branch_preset(measure: Measure, mnemonic: &str, path: &str) -> Option<Preset> {
+    // DBcc branches when the condition is false and the counter doesn't expire
+    let (condition, counter) = match (measure, path) {
+        (Measure::Branch, "taken") => (true, 1),
+        (Measure::Branch, "not_taken") => (false, 1),
+        (Measure::DecrementBranch, "taken") => (false, 1),
+        (Measure::DecrementBranch, "not_taken") => (true, 1),
+        (Measure::DecrementBranch, "expired") => (false, 0),
+        _ => panic!("unknown branch path {}", path),
+    };
+
+    (0..16).find(|&ccr| condition_holds(mnemonic, ccr) == condition).map(|ccr| {
+        let mut d = Preset::default().d;
+        d[0] = counter;
+
+        Preset { sr: 0x2700 | ccr, d, ..Preset::default() }
+    })
+}
+
+fn generate_statements_branch(context: &mut Context, cpu: Cpu, size: Option<Size>,
+                              inst: &Instruction) -> Vec<BuildResult> {
+    let mut statements = Vec::new();
+
+    let matrix = inst.matrix.unwrap();
+
+    for mnemonic in matrix[0] {
+        for path in matrix[1] {
+            let statement = match inst.measure {
+                Measure::DecrementBranch => format!("{} d0,*+10", mnemonic.name),
+                _ => format!("{}{} *+10", mnemonic.name, size.map(|s| s.suffix()).unwrap_or("")),
+            };
+
+            statements.push(BuildResult {
+                src: Some(*mnemonic),
+                dst: *path,
+                statement,
+                code: Vec::new(),
+                preset: branch_preset(inst.measure, mnemonic.name, path.name),
+                cycle_count: None,
+                bus: None,
+                fault: None,
+                trace: Vec::new(),
+                cases: None,
+                rejection: None,
+            });
+        }
+    }
+
+    assemble_statements(context, cpu, &mut statements);
+
+    statements
+}
+
+// Runs the statement built for every value, each with its own register preset
+fn generate_sweep_table<'a, F>(context: &mut Context, cpu: Cpu, name: &str, size: Option<Size>, values: Vec<u32>,
+                               build: F) -> TimingTable<'a>
+    where F: Fn(u32) -> (String, Preset)
+{
+    let mut statements = Vec::with_capacity(values.len());
+
+    for &n in &values {
+        let (statement, preset) = build(n);
+
+        statements.push(BuildResult {
+            src: None,
+            dst: Op::new("", ""),
+            statement,
+            code: Vec::new(),
+            preset: Some(preset),
+            cycle_count: None,
+            bus: None,
+            fault: None,
+            trace: Vec::new(),
+            cases: None,
+            rejection: None,
+        });
+    }
+
+    assemble_statements(context, cpu, &mut statements);
+    compile_cycle_counts(context, name, &mut statements);
+
+    TimingTable {
+        name: name.to_owned(),
+        size,
+        layout: TableLayout::Sweep(values),
+        statements,
+        caption: None,
+    }
+}
+
+// Immediate counts 1-8 and register counts 0-63 (the count is taken modulo 64) for every
+// size plus the memory form, which only exists as .w and always shifts by one.
+fn generate_shift_tables<'a>(context: &mut Context, cpu: Cpu, inst: &Instruction<'a>) -> Vec<Table<'a>> {
+    let mut tables = Vec::new();
+
+    for size in inst.sizes() {
+        let mnemonic = format!("{}{}", inst.name, size.suffix());
+
+        let imm_name = format!("{} #n,Dn", mnemonic);
+        tables.push(Table::Measured(generate_sweep_table(context, cpu, &imm_name, Some(size), (1..9).collect(), |n| {
+            (format!("{} #{},d1", mnemonic, n), Preset::default())
+        })));
+
+        let reg_name = format!("{} Dn,Dn", mnemonic);
+        tables.push(Table::Measured(generate_sweep_table(context, cpu, &reg_name, Some(size), (0..64).collect(), |n| {
+            let mut preset = Preset::default();
+            preset.d[0] = n;
+            (format!("{} d0,d1", mnemonic), preset)
+        })));
+
+        if size == Size::Word {
+            let name = format!("{} ea", mnemonic);
+            let matrix = inst.matrix.unwrap();
+            let mut statements = generate_statements_one_arg(context, cpu, &mnemonic, matrix, Preset::default());
+            compile_cycle_counts(context, &name, &mut statements);
+
+            tables.push(Table::Measured(TimingTable {
+                name,
+                size: Some(size),
+                layout: TableLayout::Row(inst.matrix.unwrap()[0]),
+                statements,
+                caption: None,
+            }));
+        }
+    }
+
+    tables
+}
+
+// The first n registers of d0-d7/a0-a7
+fn register_list(n: u32) -> String {
+    let range = |reg: char, count: u32| match count {
+        1 => format!("{}0", reg),
+        _ => format!("{}0-{}{}", reg, reg, count - 1),
+    };
+
+    if n <= 8 {
+        range('d', n)
+    } else {
+        format!("{}/{}", range('d', 8), range('a', n - 8))
+    }
+}
+
+// One sweep over 1-16 registers per size, direction and addressing mode
+fn generate_register_list_tables<'a>(context: &mut Context, cpu: Cpu, inst: &Instruction<'a>) -> Vec<Table<'a>> {
+    let mut tables = Vec::new();
+    let matrix = inst.matrix.unwrap();
+
+    for size in inst.sizes() {
+        let mnemonic = format!("{}{}", inst.name, size.suffix());
+
+        for src in matrix[0] {
+            let name = format!("{} {},list", mnemonic, src.print_name);
+
+            tables.push(Table::Measured(generate_sweep_table(context, cpu, &name, Some(size), (1..17).collect(), |n| {
+                (format!("{} {},{}", mnemonic, src.name, register_list(n)), Preset::default())
+            })));
+        }
+
+        for dst in matrix[1] {
+            let name = format!("{} list,{}", mnemonic, dst.print_name);
+
+            tables.push(Table::Measured(generate_sweep_table(context, cpu, &name, Some(size), (1..17).collect(), |n| {
+                (format!("{} {},{}", mnemonic, register_list(n), dst.name), Preset::default())
+            })));
+        }
+    }
+
+    tables
+}
+
 fn fill_table_space(name: &str, extra_chars: usize) {
     for _ in name.chars() {
         print!("-")
@@ -509,58 +1081,438 @@ fn print_predef_table(name: &str, table: &[&[&'static str]]) {
     println!("");
 }
 
-fn print_table_no_args(name: &str, build_res: &Vec<BuildResult>) {
+fn print_table_no_args(name: &str, cell: &str) {
     print!("| {} ", name);
-    println!("| {} |", build_res[0].cycle_count.unwrap());
+    println!("| {} |", cell);
 
     print!("|");
     fill_table_space(name, 2);
 
     print!("|");
 
-    fill_table_space("99", 2);
+    fill_table_space(cell, 2);
     println!("|\n");
 }
 
 
-fn generate_table(name: &str, inst: &Instruction) {
-    let mut statements;
+// title is the table name with the labels appended
+fn generate_table<'a>(context: &mut Context, cpu: Cpu, title: &str, size: Option<Size>,
+                      inst: &Instruction<'a>, matrix: &'a [&'a [Op]], preset: Preset) -> TimingTable<'a> {
+    let name = &format!("{}{}", inst.name, size.map(|s| s.suffix()).unwrap_or(""));
+    let branch = inst.measure == Measure::Branch || inst.measure == Measure::DecrementBranch;
 
-    if let Some(over) = inst.override_output_b {
-        print_predef_table(name, over);
+    let (mut statements, layout) = if branch {
+        (generate_statements_branch(context, cpu, size, inst), TableLayout::Grid(matrix[0], matrix[1]))
+    } else if matrix.len() == 2 {
+        (generate_statements_two_args(context, cpu, name, matrix, preset), TableLayout::Grid(matrix[0], matrix[1]))
+    } else if matrix.len() == 1 && matrix[0].len() > 0 {
+        (generate_statements_one_arg(context, cpu, name, matrix, preset), TableLayout::Row(matrix[0]))
+    } else {
+        (generate_statements_no_args(context, cpu, name, preset), TableLayout::Single)
+    };
+
+    compile_cycle_counts(context, title, &mut statements);
+
+    TimingTable {
+        name: title.to_owned(),
+        size,
+        layout,
+        statements,
+        caption: match inst.measure {
+            Measure::Operands => Some(operands::grid_caption(&preset)),
+            _ => None,
+        },
     }
+}
 
-    if let Some(over) = inst.override_output_w {
-        print_predef_table(name, over);
-    }
+// Generates one table per size the instruction supports. Sizes the assembler
+// rejects for every operand combination are kept so they can be reported but
+// aren't printed.
+fn generate_tables<'a>(context: &mut Context, cpu: Cpu, inst: &Instruction<'a>) -> Vec<Table<'a>> {
+    let mut tables = Vec::new();
 
-    if let Some(over) = inst.override_output_l {
-        let name_long = format!("{}.l", name);
-        print_predef_table(&name_long, over);
+    match inst.measure {
+        Measure::Shift => return generate_shift_tables(context, cpu, inst),
+        Measure::RegisterList => return generate_register_list_tables(context, cpu, inst),
+        _ => (),
     }
 
     if inst.has_override() {
-        return;
+        let overrides = [
+            (Size::Byte, inst.override_output_b),
+            (Size::Word, inst.override_output_w),
+            (Size::Long, inst.override_output_l)];
+
+        // A single override table covers the whole instruction so it keeps the plain name
+        let count = overrides.iter().filter(|o| o.1.is_some()).count();
+
+        for &(size, over) in overrides.iter() {
+            if let Some(over) = over {
+                if count > 1 {
+                    tables.push(Table::Predefined(format!("{}{}", inst.name, size.suffix()), Some(size), over));
+                } else {
+                    tables.push(Table::Predefined(inst.name.to_owned(), None, over));
+                }
+            }
+        }
+
+        return tables;
     }
 
-    let matrix = inst.matrix.unwrap();
+    let mut sizes: Vec<Option<Size>> = inst.sizes().into_iter().map(Some).collect();
 
-    if matrix.len() == 2 {
-        statements = generate_statements_two_args(name, inst);
-        compile_cycle_counts(&mut statements);
-        print_grid_table(name, &statements, matrix[0], matrix[1]);
-    } else if matrix.len() == 1 && matrix[0].len() > 0 {
-        statements = generate_statements_one_arg(name, inst);
-        compile_cycle_counts(&mut statements);
-        print_table(name, &statements, matrix[0]);
-    } else {
-        statements = generate_statements_no_args(name);
-        compile_cycle_counts(&mut statements);
-        print_table_no_args(&name, &statements);
+    if sizes.is_empty() {
+        sizes.push(None);
+    }
+
+    for size in sizes {
+        let name = match size {
+            Some(size) => format!("{}{}", inst.name, size.suffix()),
+            None => inst.name.to_owned(),
+        };
+
+        for (matrix_label, matrix) in inst.matrices(cpu) {
+            for (label, preset) in inst.labelled_presets() {
+                let labels: Vec<&str> = label.into_iter().chain(matrix_label).collect();
+
+                let title = match labels.is_empty() {
+                    true => name.clone(),
+                    false => format!("{} ({})", name, labels.join(", ")),
+                };
+
+                tables.push(Table::Measured(generate_table(context, cpu, &title, size, inst, matrix, preset)));
+            }
+        }
+    }
+
+    if inst.measure == Measure::Operands {
+        tables.push(Table::Measured(operands::generate_table(context, cpu, inst.name)));
+    }
+
+    tables
+}
+
+fn generate_chapters<'a>(context: &mut Context, cpu: Cpu, ea_times: &ea::EaTimes) -> Vec<Chapter<'a>> {
+    vec![exceptions::generate_chapter(context, cpu), ea::generate_chapter(cpu, ea_times)]
+}
+
+fn generate_docs<'a, 'b>(context: &mut Context, cpu: Cpu, instructions: &'b [Instruction<'a>]) -> DocSet<'a, 'b> {
+    let mut instructions: Vec<_> = instructions.iter()
+        .filter(|inst| inst.min_cpu <= cpu)
+        .map(|inst| (inst, generate_tables(context, cpu, inst)))
+        .collect();
+
+    if cpu.has_instruction_cache() {
+        for &mut (_, ref mut tables) in &mut instructions {
+            cases::add_cases(tables);
+        }
+    }
+
+    let ea_times = ea::derive(&instructions);
+
+    DocSet {
+        cpu,
+        chapters: generate_chapters(context, cpu, &ea_times),
+        instructions,
+        ea_times,
+    }
+}
+
+// Sweeps are printed 16 values per row with a row of n above each row of cycles
+fn print_sweep_table(name: &str, values: &[u32], cells: &[Option<String>]) {
+    // Sweep names such as "asl.w Dn,Dn" don't fit the usual first column
+    let label_width = name.len().max(9);
+
+    for (i, (values, cells)) in values.chunks(16).zip(cells.chunks(16)).enumerate() {
+        let label = if i == 0 { name } else { "n" };
+
+        // Every column is as wide as its value or cell, whichever is longer, as in print_table_header()
+        let columns: Vec<(String, &str)> = values.iter().zip(cells)
+            .map(|(n, cell)| (n.to_string(), cell.as_ref().map_or("*", String::as_str)))
+            .collect();
+
+        print!("| {name:<width$}", name = label, width = label_width);
+
+        for &(ref n, cell) in &columns {
+            print!("| {n:<width$} ", n = n, width = n.len().max(cell.len()));
+        }
+
+        println!("|");
+
+        if i == 0 {
+            print!("|{}", "-".repeat(label_width + 1));
+
+            for &(ref n, cell) in &columns {
+                print!("|{}", "-".repeat(n.len().max(cell.len()) + 2));
+            }
+
+            println!("|");
+        }
+
+        print!("| {name:<width$}", name = "cycles", width = label_width);
+
+        for &(ref n, cell) in &columns {
+            print!("|{cell:^width$}", cell = cell, width = n.len().max(cell.len()) + 2);
+        }
+
+        println!("|");
+    }
+
+    println!();
+}
+
+fn print_layout(name: &str, layout: &TableLayout, cells: &[Option<String>]) {
+    match *layout {
+        TableLayout::Grid(src, dst) => print_grid_table(name, cells, src, dst),
+        TableLayout::Row(dst) => print_table(name, cells, dst),
+        TableLayout::Single => print_table_no_args(name, cells[0].as_deref().unwrap_or("*")),
+        TableLayout::Sweep(ref values) => print_sweep_table(name, values, cells),
+        // Too many cells to list, print_operand_sweep() summarises them
+        TableLayout::Operands(..) => (),
+    }
+}
+
+// Prints the fitted formula above a sweep and marks the values it doesn't match with '!'
+fn print_sweep(table: &TimingTable, format: CellFormat) {
+    let points = table.sweep_points();
+    let mut cells = cycle_cells(&table.statements, format);
+
+    let fit = match sweep::fit(&points) {
+        Some(fit) => fit,
+        None => return,
+    };
+
+    let mismatches = sweep::mismatches(&fit, &points);
+
+    if let TableLayout::Sweep(ref values) = table.layout {
+        for (cell, n) in cells.iter_mut().zip(values) {
+            if mismatches.iter().any(|m| m.0 == *n) {
+                *cell = cell.as_ref().map(|c| format!("{}!", c));
+            }
+        }
+    }
+
+    println!("{}: `{}`\n", table.name, fit.formula());
+
+    print_layout(&table.name, &table.layout, &cells);
+
+    for &(n, cycles) in &mismatches {
+        println!("* n = {}: measured {} cycles, formula gives {}", n, cycles, fit.cycles(n));
+    }
+
+    if !mismatches.is_empty() {
+        println!();
+    }
+}
+
+// Mismatches listed below an operand sweep, the rest are only counted
+const LISTED_MISMATCHES: usize = 8;
+
+// Prints the range of an operand sweep, the fitted formula and the cycles measured for every n
+fn print_operand_sweep(table: &TimingTable, n_meaning: &str) {
+    let measured = operands::measured(table);
+
+    let (summary, fit) = match (operands::summary(&measured), sweep::fit(&table.sweep_points())) {
+        (Some(summary), Some(fit)) => (summary, fit),
+        _ => return,
+    };
+
+    let mismatches = operands::mismatches(&fit, &measured);
+
+    println!("{}: {} operand values, min {}, max {}, average {:.1} cycles, `{}` with n = {}\n",
+             table.name, measured.len(), summary.min, summary.max, summary.average, fit.formula(), n_meaning);
+
+    println!("| n  | values | cycles |");
+    println!("|----|--------|--------|");
+
+    for group in operands::groups(&measured) {
+        println!("| {:<3}| {:<7}| {:<7}|", group.n, group.values, operands::group_cycles(&group));
+    }
+
+    println!();
+
+    for &(point, cycles) in mismatches.iter().take(LISTED_MISMATCHES) {
+        println!("* {}: measured {} cycles, formula gives {}", operands::describe(&point), cycles, fit.cycles(point.n));
+    }
+
+    if mismatches.len() > LISTED_MISMATCHES {
+        println!("* {} more values don't match the formula", mismatches.len() - LISTED_MISMATCHES);
+    }
+
+    if !mismatches.is_empty() {
+        println!();
+    }
+}
+
+fn print_tables(tables: &[Table], format: CellFormat) {
+    for table in tables {
+        match *table {
+            Table::Predefined(ref name, _, rows) => print_predef_table(name, rows),
+            Table::Measured(ref table) => {
+                if !has_cycle_counts(&table.statements) {
+                    continue;
+                }
+
+                if let Some(ref caption) = table.caption {
+                    println!("{}\n", caption);
+                }
+
+                match table.layout {
+                    TableLayout::Sweep(_) => print_sweep(table, format),
+                    TableLayout::Operands(_, n_meaning) => print_operand_sweep(table, n_meaning),
+                    _ => print_layout(&table.name, &table.layout, &cycle_cells(&table.statements, format)),
+                }
+            }
+        }
+    }
+}
+
+fn print_chapter(chapter: &Chapter, format: CellFormat) {
+    println!("## {}\n", chapter.title);
+    println!("{}\n", chapter.text);
+
+    print_tables(&chapter.tables, format);
+}
+
+// Prints every grid table over source addressing modes as base + EA with the cells that don't fit
+fn print_decompositions(tables: &[Table], ea_times: &ea::EaTimes) {
+    for table in tables {
+        let (table, decomposition) = match *table {
+            Table::Measured(ref table) => match ea::decompose(table, ea_times) {
+                Some(ref decomposition) if decomposition.bases.is_empty() => continue,
+                Some(decomposition) => (table, decomposition),
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let bases: Vec<String> = decomposition.bases.iter().map(|&(dst, base)| format!("{}: {}+EA", dst, base)).collect();
+
+        println!("{} as base + EA: {}\n", table.name, bases.join(", "));
+
+        for anomaly in &decomposition.anomalies {
+            println!("* anomaly {},{}: measured {}, base + EA gives {}",
+                     anomaly.src, anomaly.dst, anomaly.measured, anomaly.expected);
+        }
+
+        if !decomposition.anomalies.is_empty() {
+            println!();
+        }
+    }
+}
+
+fn print_docs(set: &DocSet, format: CellFormat, access_trace: bool) {
+    println!("# MC{}\n", set.cpu.name());
+
+    if set.cpu.has_instruction_cache() {
+        println!("{}\n", CASES_NOTE);
+    }
+
+    for &(inst, ref tables) in &set.instructions {
+        print_instruction_header(inst);
+        print_tables(tables, format);
+        print_decompositions(tables, &set.ea_times);
+
+        if access_trace {
+            print_access_sequences(tables);
+        }
+    }
+
+    for chapter in &set.chapters {
+        print_chapter(chapter, format);
+
+        if access_trace {
+            print_access_sequences(&chapter.tables);
+        }
+    }
+}
+
+// Lists the bus cycles of every traced statement in the order the instruction made them.
+// Operand sweeps run the same code for every value so they are left out
+fn print_access_sequences(tables: &[Table]) {
+    for table in tables {
+        if let Table::Measured(ref table) = *table {
+            if !table.has_access_trace() {
+                continue;
+            }
+
+            println!("Access sequence of {}:\n", table.name);
+
+            if table.has_split_accesses() {
+                println!("{}\n", SPLIT_NOTE);
+            }
+
+            for result in table.statements.iter().filter(|result| !result.trace.is_empty()) {
+                let accesses: Vec<String> = result.trace.iter().map(|access| access.describe()).collect();
+                println!("* `{}`: {}", result.statement, accesses.join(", "));
+            }
+
+            println!();
+        }
+    }
+}
+
+fn report_unused_descriptions(instructions: &[Instruction]) {
+    for &(name, desc) in DESCRIPTIONS {
+        if !instructions.iter().any(|inst| inst.desc.as_ref() == Some(desc)) {
+            eprintln!("warning: {} is not used by any instruction", name);
+        }
+    }
+}
+
+// Writes the new cache entries and reports the result of --verify-cache, exits with an
+// error if cached entries didn't match
+fn save_cache(context: &mut Context) {
+    let cache = match context.cache {
+        Some(ref mut cache) => cache,
+        None => return,
+    };
+
+    if let Err(err) = cache.save() {
+        eprintln!("error: unable to write the cache: {}", err);
+        process::exit(1);
+    }
+
+    if let Some((verified, mismatches)) = cache.verify_summary() {
+        eprintln!("cache: {} entries verified, {} mismatches", verified, mismatches);
+
+        if mismatches > 0 {
+            process::exit(1);
+        }
+    }
+}
+
+// Prints the rejection summary, writes the diagnostics report and exits with an error
+// if the assembler failed
+fn report_diagnostics(context: &Context, options: &Options) {
+    let diagnostics = &context.diagnostics;
+
+    if let Some(summary) = diagnostics.summary() {
+        eprintln!("{}", summary);
+    }
+
+    if let Some(ref path) = options.diagnostics {
+        if let Err(err) = File::create(path).and_then(|mut file| diagnostics.write_report(&mut file)) {
+            eprintln!("error: unable to write the diagnostics report to {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+
+    if diagnostics.has_tool_failures() {
+        eprintln!("error: the assembler failed, the affected cells are incomplete (see --diagnostics)");
+        process::exit(1);
     }
 }
 
 fn main() {
+    let options = match Options::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, options::USAGE);
+            process::exit(1);
+        }
+    };
+
     let dest_types = [
         Op::new("d0", "Dn"),
         Op::new("a0", "An"),
@@ -569,8 +1521,8 @@ fn main() {
         Op::new("-(a0)", "-(An)"),
         Op::new("2(a0)", "d(An)"),
         Op::new("2(a0,d0)", "d(An,Dn)"),
-        Op::new("$4.W", "xxx.W"),
-        Op::new("$4.L", "xxx.L")];
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L")];
 
     let src_types = [
         Op::new("d0", "Dn"),
@@ -580,12 +1532,157 @@ fn main() {
         Op::new("-(a0)", "-(An)"),
         Op::new("2(a0)", "d(An)"),
         Op::new("2(a0,d0)", "d(An,Dn)"),
-        Op::new("$4.W", "xxx.W"),
-        Op::new("$4.L", "xxx.L"),
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L"),
         Op::new("2(pc)", "d(PC)"),
         Op::new("2(pc,d0)", "d(PC,Dn)"),
         Op::new("#8", "#xxx")];
 
+    let mem_types = [
+        Op::new("(a0)", "(An)"),
+        Op::new("(a0)+", "(An)+"),
+        Op::new("-(a0)", "-(An)"),
+        Op::new("2(a0)", "d(An)"),
+        Op::new("2(a0,d0)", "d(An,Dn)"),
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L")];
+
+    let movem_src_types = [
+        Op::new("(a0)", "(An)"),
+        Op::new("(a0)+", "(An)+"),
+        Op::new("2(a0)", "d(An)"),
+        Op::new("2(a0,d0)", "d(An,Dn)"),
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L"),
+        Op::new("2(pc)", "d(PC)"),
+        Op::new("2(pc,d0)", "d(PC,Dn)")];
+
+    let movem_dst_types = [
+        Op::new("(a0)", "(An)"),
+        Op::new("-(a0)", "-(An)"),
+        Op::new("2(a0)", "d(An)"),
+        Op::new("2(a0,d0)", "d(An,Dn)"),
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L")];
+
+    // Control addressing modes. The targets are the NOP landing area or, for the PC relative
+    // modes, the next instruction so jumps always land on valid code
+    let control_types = [
+        Op::new("(a0)", "(An)"),
+        Op::new("2(a0)", "d(An)"),
+        Op::new("1(a0,d0)", "d(An,Dn)"),
+        Op::new("$800.W", "xxx.W"),
+        Op::new("$800.L", "xxx.L"),
+        Op::new("2(pc)", "d(PC)"),
+        Op::new("1(pc,d0)", "d(PC,Dn)")];
+
+    // 68020 full format modes. The memory indirect ones fetch their pointer from an exception
+    // vector, which holds the NOP landing area, so they end up at $800 like the 68000 modes.
+    // The PC relative indirect ones suppress the PC (zpc) to reach the vector from any address
+    let src_020_types = [
+        Op::new("d0", "Dn"),
+        Op::new("a0", "An"),
+        Op::new("(2,a0,d0.l*4)", "(d8,An,Dn.L*4)"),
+        Op::new("($ff.w,a0,d0.l)", "(bd,An,Xn)"),
+        Op::new("([-$7f8,a0],d0.l,$3ff)", "([bd,An],Xn,od)"),
+        Op::new("([-$7f9,a0,d0.l],$400)", "([bd,An,Xn],od)"),
+        Op::new("(2,pc,d0.l*4)", "(d8,PC,Dn.L*4)"),
+        Op::new("($ff.w,pc,d0.l)", "(bd,PC,Xn)"),
+        Op::new("([8,zpc],d0.l,$3ff)", "([bd,PC],Xn,od)"),
+        Op::new("([7,zpc,d0.l],$400)", "([bd,PC,Xn],od)")];
+
+    let dest_020_types = [
+        Op::new("d0", "Dn"),
+        Op::new("a0", "An"),
+        Op::new("(2,a0,d0.l*4)", "(d8,An,Dn.L*4)"),
+        Op::new("($ff.w,a0,d0.l)", "(bd,An,Xn)"),
+        Op::new("([-$7f8,a0],d0.l,$3ff)", "([bd,An],Xn,od)"),
+        Op::new("([-$7f9,a0,d0.l],$400)", "([bd,An,Xn],od)")];
+
+    let mem_020_types = [
+        Op::new("(2,a0,d0.l*4)", "(d8,An,Dn.L*4)"),
+        Op::new("($ff.w,a0,d0.l)", "(bd,An,Xn)"),
+        Op::new("([-$7f8,a0],d0.l,$3ff)", "([bd,An],Xn,od)"),
+        Op::new("([-$7f9,a0,d0.l],$400)", "([bd,An,Xn],od)")];
+
+    // The PC relative displacements target the next instruction
+    let control_020_types = [
+        Op::new("(2,a0,d0.l*4)", "(d8,An,Dn.L*4)"),
+        Op::new("($ff.w,a0,d0.l)", "(bd,An,Xn)"),
+        Op::new("([-$7f8,a0],d0.l,$3ff)", "([bd,An],Xn,od)"),
+        Op::new("([-$7f9,a0,d0.l],$400)", "([bd,An,Xn],od)"),
+        Op::new("(-2,pc,d0.l*4)", "(d8,PC,Dn.L*4)"),
+        Op::new("(3.w,pc,d0.l)", "(bd,PC,Xn)"),
+        Op::new("([8,zpc],d0.l,$3ff)", "([bd,PC],Xn,od)"),
+        Op::new("([7,zpc,d0.l],$400)", "([bd,PC,Xn],od)")];
+
+    let imm_types = [Op::new("#8", "#xxx")];
+    let an_types = [Op::new("a0", "An")];
+    let ccr_types = [Op::new("ccr", "CCR")];
+    let sr_types = [Op::new("sr", "SR")];
+    let usp_types = [Op::new("usp", "USP")];
+    let disp_types = [Op::new("#-4", "#xxx")];
+    let vector_types = [Op::new("#0", "#xxx")];
+    let rn_types = [Op::new("d0", "Dn"), Op::new("a0", "An")];
+
+    let ctrl_types = [
+        Op::new("sfc", "SFC"),
+        Op::new("dfc", "DFC"),
+        Op::new("usp", "USP"),
+        Op::new("vbr", "VBR"),
+        Op::new("cacr", "CACR"),
+        Op::new("caar", "CAAR"),
+        Op::new("msp", "MSP"),
+        Op::new("isp", "ISP")];
+
+    let bcc_types = [
+        Op::new("bhi", "HI"),
+        Op::new("bls", "LS"),
+        Op::new("bcc", "CC"),
+        Op::new("bcs", "CS"),
+        Op::new("bne", "NE"),
+        Op::new("beq", "EQ"),
+        Op::new("bvc", "VC"),
+        Op::new("bvs", "VS"),
+        Op::new("bpl", "PL"),
+        Op::new("bmi", "MI"),
+        Op::new("bge", "GE"),
+        Op::new("blt", "LT"),
+        Op::new("bgt", "GT"),
+        Op::new("ble", "LE")];
+
+    let dbcc_types = [
+        Op::new("dbt", "T"),
+        Op::new("dbf", "F"),
+        Op::new("dbhi", "HI"),
+        Op::new("dbls", "LS"),
+        Op::new("dbcc", "CC"),
+        Op::new("dbcs", "CS"),
+        Op::new("dbne", "NE"),
+        Op::new("dbeq", "EQ"),
+        Op::new("dbvc", "VC"),
+        Op::new("dbvs", "VS"),
+        Op::new("dbpl", "PL"),
+        Op::new("dbmi", "MI"),
+        Op::new("dbge", "GE"),
+        Op::new("dblt", "LT"),
+        Op::new("dbgt", "GT"),
+        Op::new("dble", "LE")];
+
+    let bra_types = [Op::new("bra", "BRA")];
+    let bsr_types = [Op::new("bsr", "BSR")];
+
+    let taken_types = [Op::new("taken", "Taken")];
+
+    let branch_types = [
+        Op::new("taken", "Taken"),
+        Op::new("not_taken", "Not taken")];
+
+    let dbcc_path_types = [
+        Op::new("taken", "Taken"),
+        Op::new("not_taken", "Not taken"),
+        Op::new("expired", "Counter expired")];
+
     let cc_codes: &[&[&'static str]] = &[
         &["Mnemonic", "Condition", "Mnemonic", "Condition"],
         &["CC (HI)", "Carry Clear","LS","Low or Same"],
@@ -600,394 +1697,871 @@ fn main() {
     let two_ops: &[&[Op]] = &[&src_types, &dest_types];
     let one_op: &[&[Op]] = &[&dest_types];
     let no_ops: &[&[Op]] = &[&[]];
-
-    let shift_desc: &[&[&'static str]] = &[
-        &["Dn", "An", "(An)", "(An)+", "-(An)", "d(An)", "d(An,Dn)", "xxx.W", "xxx.L"],
-        &["#1", "8","*","12","12","14","16","18","16","20"],
-        &["#1-8", "6+2n", "*", "*", "*", "*", "*", "*", "*", "*"],
-        &["Dn", "6+2n", "*", "*", "*", "*", "*", "*", "*", "*"]];
-
-    let shift_desc_long: &[&[&'static str]] = &[
-        &["Dn", "An", "(An)", "(An)+", "-(An)", "d(An)", "d(An,Dn)", "xxx.W", "xxx.L"],
-        &["#1-8", "8+2n", "*", "*", "*", "*", "*", "*", "*", "*"],
-        &["Dn", "8+2n", "*", "*", "*", "*", "*", "*", "*", "*"]];
-
-    let bcc_desc: &[&[&'static str]] = &[
-        &["Displacement", "Branch Taken", "Branch Not Taken"],
-        &["", "Byte", "10", "8"],
-        &["", "Word", "10", "12"]];
-
-    let bsr_desc: &[&[&'static str]] = &[
-        &["Displacement", "Branch Taken", "Branch Not Taken"],
-        &["", "Byte", "18", "-"],
-        &["", "Word", "18", "-"]];
-
-    let dbcc_desc: &[&[&'static str]] = &[
-        &["Displacement", "Branch Taken", "Branch Not Taken"],
-        &["", "cc true", "-", "12"],
-        &["", "cc false, Count not Expired", "10", "-"],
-        &["", "cc false, Counter Expired", "-", "14"]];
-
-    let branch_header: &[&'static str] = 
-        &["(An)", "(d16,An)", "(d8,An,Xn)", "(xxx).W", "(xxx).L", "(d16,PC)", "(d8,PC,Xn)"];
-
-    let jmp_desc: &[&[&'static str]] = &[
-       branch_header,
-       &["", "8", "10", "14", "10", "12", "10", "14"]];
-
-    let jsr_desc: &[&[&'static str]] = &[
-       branch_header,
-       &["", "16", "18", "22", "18", "20", "18", "22"]];
-
-    let lea_desc: &[&[&'static str]] = &[
-       branch_header,
-       &["", "4", "8", "12", "8", "12", "8", "12"]];
-
-    let movem_desc: &[&[&'static str]] = &[
-        &["Size", "(An)", "(An)+", "-(An)", "(d16,An)", "(d8,An,Xn)", "(xxx).W", "(xxx).L", "(d16,PC)", "(d8,PC,Xn)"],
-        &["M -> R", "Word", "12+4n", "12+4n", "-", "16+4n", "18+4n", "16+4n", "20+4n", "16+4n", "18+4n"],
-        &["M -> R", "Long", "12+8n", "12+8n", "-", "16+8n", "18+8n", "16+8n", "20+8n", "16+8n", "18+8n"],
-        &["R -> M", "Word", "8+4n", "-", "8+4n", "12+4n", "14+4n", "12+4n", "16+4n", "-", "-"],
-        &["R -> M", "Long", "8+8n", "-", "8+8n", "12+8n", "14+8n", "12+8n", "16+8n", "-", "-"]];
-
-    let trap_desc: &[&[&'static str]] = &[
-        &["# <vector> "],
-        &["", "34"]];
-
-    let trapv_desc: &[&[&'static str]] = &[&["34"]];
-
-    //let pea_desc: &[&[&'static str]] = &[
-     //  branch_header ,
-     //  &["12", "16", "20", "16", "20", "16", "20"]];
-
-    unsafe {
-        m68k_wrapper_init();
-    }
+    let an_op: &[&[Op]] = &[&an_types];
+    let mem_op: &[&[Op]] = &[&mem_types];
+    let control_op: &[&[Op]] = &[&control_types];
+    let control_to_an: &[&[Op]] = &[&control_types, &an_types];
+    let movem_ops: &[&[Op]] = &[&movem_src_types, &movem_dst_types];
+    let imm_to_ccr: &[&[Op]] = &[&imm_types, &ccr_types];
+    let imm_to_sr: &[&[Op]] = &[&imm_types, &sr_types];
+    let to_ccr: &[&[Op]] = &[&src_types, &ccr_types];
+    let to_sr: &[&[Op]] = &[&src_types, &sr_types];
+    let from_sr: &[&[Op]] = &[&sr_types, &dest_types];
+    let to_usp: &[&[Op]] = &[&an_types, &usp_types];
+    let from_usp: &[&[Op]] = &[&usp_types, &an_types];
+    let link_ops: &[&[Op]] = &[&an_types, &disp_types];
+    let imm_op: &[&[Op]] = &[&imm_types];
+    let vector_op: &[&[Op]] = &[&vector_types];
+    let from_ccr: &[&[Op]] = &[&ccr_types, &dest_types];
+    let to_ctrl: &[&[Op]] = &[&rn_types, &ctrl_types];
+    let from_ctrl: &[&[Op]] = &[&ctrl_types, &rn_types];
+    let bcc_ops: &[&[Op]] = &[&bcc_types, &branch_types];
+    let bra_ops: &[&[Op]] = &[&bra_types, &taken_types];
+    let bsr_ops: &[&[Op]] = &[&bsr_types, &taken_types];
+    let dbcc_ops: &[&[Op]] = &[&dbcc_types, &dbcc_path_types];
+
+    let two_ops_020: &[CpuMatrix] = &[CpuMatrix { min_cpu: Cpu::M68EC020, label: "68020 modes", matrix: &[&src_020_types, &dest_020_types] }];
+    let one_op_020: &[CpuMatrix] = &[CpuMatrix { min_cpu: Cpu::M68EC020, label: "68020 modes", matrix: &[&mem_020_types] }];
+    let control_op_020: &[CpuMatrix] = &[CpuMatrix { min_cpu: Cpu::M68EC020, label: "68020 modes", matrix: &[&control_020_types] }];
+    let control_to_an_020: &[CpuMatrix] =
+        &[CpuMatrix { min_cpu: Cpu::M68EC020, label: "68020 modes", matrix: &[&control_020_types, &an_types] }];
+
+    // SCC sets a data register in 6 cycles and clears it in 4 on the 68000, the carry decides
+    let scc_presets = [
+        LabelledPreset { label: "true", preset: Preset { sr: 0x2700, .. DEFAULT_PRESET } },
+        LabelledPreset { label: "false", preset: Preset { sr: 0x2701, .. DEFAULT_PRESET } }];
+
+    // Musashi ends the timeslice on STOP so the measured count is always zero
+    let stop_desc: &[&[&'static str]] = &[
+        &["# <data> "],
+        &["", "4"]];
 
     let inst_2_ops_000 =
         [
         Instruction {
             name: "abcd",
             desc: Some(ABCD_DESC),
+            category: Category::Bcd,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "add",
             desc: Some(ADD_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "adda",
+            desc: Some(ADDA_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "addi",
+            desc: Some(ADDI_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "addq",
             desc: Some(ADDQ_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "addx",
+            desc: Some(ADDX_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "and",
+            desc: Some(AND_DESC),
+            category: Category::Logical,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "andi",
+            desc: Some(ANDI_DESC),
+            category: Category::Logical,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "andi",
+            title: Some("ANDI to CCR"),
+            desc: Some(ANDI_CCR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_ccr),
             .. Instruction::default()
         },
         Instruction {
-            name: "addx",
-            desc: Some(ADDX_DESC),
-            matrix: Some(two_ops),
+            name: "andi",
+            title: Some("ANDI to SR"),
+            desc: Some(ANDI_SR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_sr),
             .. Instruction::default()
         },
         Instruction {
-            name: "and",
-            desc: Some(AND_DESC),
-            matrix: Some(two_ops),
+            name: "asl",
+            desc: Some(ASL_ASR_DESC),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
-            name: "asl",
+            name: "asr",
             desc: Some(ASL_ASR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "bcc",
+            title: Some("Bcc"),
             desc: Some(BCC_DESC),
+            category: Category::ProgramControl,
+            measure: Measure::Branch,
+            matrix: Some(bcc_ops),
             cc_codes: Some(&cc_codes),
-            override_output_w: Some(&bcc_desc),
             .. Instruction::default()
         },
         Instruction {
             name: "bchg",
             desc: Some(BCHG_DESC),
+            category: Category::BitManipulation,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "bclr",
             desc: Some(BCLR_DESC),
+            category: Category::BitManipulation,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "bkpt",
+            desc: Some(BKPT_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68010,
+            matrix: Some(vector_op),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "bra",
+            desc: Some(BRA_DESC),
+            category: Category::ProgramControl,
+            measure: Measure::Branch,
+            matrix: Some(bra_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "bset",
             desc: Some(BSET_DESC),
+            category: Category::BitManipulation,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "bsr",
             desc: Some(BSR_DESC),
-            override_output_w: Some(&bsr_desc),
+            category: Category::ProgramControl,
+            measure: Measure::Branch,
+            matrix: Some(bsr_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "btst",
             desc: Some(BTST_DESC),
+            category: Category::BitManipulation,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "chk",
+            desc: Some(CHK_DESC),
+            category: Category::SystemControl,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "chk",
+            title: Some("CHK.L"),
+            desc: Some(CHK_L_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68020,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "chk2",
+            desc: Some(CHK2_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68020,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "clr",
             desc: Some(CLR_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "cmp",
             desc: Some(CMP_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "cmp2",
+            desc: Some(CMP2_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "cmpa",
+            desc: Some(CMPA_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "cmpi",
+            desc: Some(CMPI_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "cmpm",
+            desc: Some(CMPM_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "dbcc",
+            title: Some("DBcc"),
             desc: Some(DBCC_DESC),
+            category: Category::ProgramControl,
+            measure: Measure::DecrementBranch,
+            matrix: Some(dbcc_ops),
             cc_codes: Some(&cc_codes),
-            override_output_w: Some(&dbcc_desc),
             .. Instruction::default()
         },
         Instruction {
             name: "divu",
             desc: Some(DIVS_DIVU_DESC),
+            category: Category::IntegerArithmetic,
+            measure: Measure::Operands,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "divs",
             desc: Some(DIVS_DIVU_DESC),
+            category: Category::IntegerArithmetic,
+            measure: Measure::Operands,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "divs",
+            title: Some("DIVS.L"),
+            desc: Some(DIVS_DIVU_L_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "divu",
+            title: Some("DIVU.L"),
+            desc: Some(DIVS_DIVU_L_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "eor",
             desc: Some(EOR_DESC),
+            category: Category::Logical,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "eori",
+            desc: Some(EORI_DESC),
+            category: Category::Logical,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "eori",
+            title: Some("EORI to CCR"),
+            desc: Some(EORI_CCR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_ccr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "eori",
+            title: Some("EORI to SR"),
+            desc: Some(EORI_SR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_sr),
             .. Instruction::default()
         },
         Instruction {
             name: "exg",
             desc: Some(EXG_DESC),
+            category: Category::DataMovement,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "ext",
             desc: Some(EXT_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(one_op),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "extb",
+            desc: Some(EXTB_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
             matrix: Some(one_op),
             .. Instruction::default()
         },
         Instruction {
             name: "illegal",
             desc: Some(ILLEGAL_DESC),
+            category: Category::SystemControl,
             matrix: Some(no_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "jmp",
             desc: Some(JMP_DESC),
-            override_output_w: Some(&jmp_desc),
+            category: Category::ProgramControl,
+            matrix: Some(control_op),
+            cpu_matrices: control_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "jsr",
             desc: Some(JSR_DESC),
-            override_output_w: Some(&jsr_desc),
+            category: Category::ProgramControl,
+            matrix: Some(control_op),
+            cpu_matrices: control_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "lea",
             desc: Some(LEA_DESC),
-            override_output_w: Some(&lea_desc),
+            category: Category::DataMovement,
+            matrix: Some(control_to_an),
+            cpu_matrices: control_to_an_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "link",
+            desc: Some(LINK_DESC),
+            category: Category::DataMovement,
+            matrix: Some(link_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "lsl",
             desc: Some(LSL_LSR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "lsr",
             desc: Some(LSL_LSR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "move",
             desc: Some(MOVE_DESC),
+            category: Category::DataMovement,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE from CCR"),
+            desc: Some(MOVE_FROM_CCR_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68010,
+            matrix: Some(from_ccr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "movea",
+            desc: Some(MOVEA_DESC),
+            category: Category::DataMovement,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE to CCR"),
+            desc: Some(MOVE_TO_CCR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(to_ccr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE from SR"),
+            desc: Some(MOVE_FROM_SR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(from_sr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE to SR"),
+            desc: Some(MOVE_TO_SR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(to_sr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE to USP"),
+            desc: Some(MOVE_USP_DESC),
+            category: Category::SystemControl,
+            matrix: Some(to_usp),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "move",
+            title: Some("MOVE from USP"),
+            desc: Some(MOVE_USP_DESC),
+            category: Category::SystemControl,
+            matrix: Some(from_usp),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "movec",
+            title: Some("MOVEC from Control Register"),
+            desc: Some(MOVEC_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68010,
+            matrix: Some(from_ctrl),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "movec",
+            title: Some("MOVEC to Control Register"),
+            desc: Some(MOVEC_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68010,
+            matrix: Some(to_ctrl),
             .. Instruction::default()
         },
         Instruction {
             name: "movem",
             desc: Some(MOVEM_DESC),
-            override_output_w: Some(&movem_desc),
+            category: Category::DataMovement,
+            measure: Measure::RegisterList,
+            matrix: Some(movem_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "movep",
             desc: Some(MOVEP_DESC),
+            category: Category::DataMovement,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "moveq",
             desc: Some(MOVEQ_DESC),
+            category: Category::DataMovement,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "moves",
+            desc: Some(MOVES_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68010,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "muls",
             desc: Some(MULS_DESC),
+            category: Category::IntegerArithmetic,
+            measure: Measure::Operands,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "muls",
+            title: Some("MULS.L"),
+            desc: Some(MULS_L_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "mulu",
             desc: Some(MULU_DESC),
+            category: Category::IntegerArithmetic,
+            measure: Measure::Operands,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "mulu",
+            title: Some("MULU.L"),
+            desc: Some(MULU_L_DESC),
+            category: Category::IntegerArithmetic,
+            min_cpu: Cpu::M68020,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "nbcd",
+            desc: Some(NBCD_DESC),
+            category: Category::Bcd,
+            matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "neg",
             desc: Some(NEG_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "negx",
             desc: Some(NEGX_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "nop",
             desc: Some(NOP_DESC),
+            category: Category::ProgramControl,
             matrix: Some(no_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "not",
             desc: Some(NOT_DESC),
+            category: Category::Logical,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             .. Instruction::default()
         },
         Instruction {
             name: "or",
             desc: Some(OR_DESC),
+            category: Category::Logical,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "ori",
+            desc: Some(ORI_DESC),
+            category: Category::Logical,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "ori",
+            title: Some("ORI to CCR"),
+            desc: Some(ORI_CCR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_ccr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "ori",
+            title: Some("ORI to SR"),
+            desc: Some(ORI_SR_DESC),
+            category: Category::SystemControl,
+            matrix: Some(imm_to_sr),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "pea",
+            desc: Some(PEA_DESC),
+            category: Category::DataMovement,
+            matrix: Some(control_op),
+            cpu_matrices: control_op_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "reset",
+            desc: Some(RESET_DESC),
+            category: Category::SystemControl,
+            matrix: Some(no_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "rol",
             desc: Some(ROL_ROR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "ror",
             desc: Some(ROL_ROR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "roxl",
             desc: Some(ROXL_ROXR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
             .. Instruction::default()
         },
         Instruction {
             name: "roxr",
             desc: Some(ROXL_ROXR_DESC),
-            matrix: Some(two_ops),
-            override_output_w: Some(&shift_desc),
-            override_output_l: Some(&shift_desc_long),
+            category: Category::ShiftRotate,
+            measure: Measure::Shift,
+            matrix: Some(mem_op),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "rtd",
+            desc: Some(RTD_DESC),
+            category: Category::ProgramControl,
+            min_cpu: Cpu::M68010,
+            matrix: Some(imm_op),
             .. Instruction::default()
         },
         Instruction {
             name: "rte",
             desc: Some(RTE_DESC),
+            category: Category::SystemControl,
+            matrix: Some(no_ops),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "rtr",
+            desc: Some(RTR_DESC),
+            category: Category::ProgramControl,
             matrix: Some(no_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "rts",
             desc: Some(RTS_DESC),
+            category: Category::ProgramControl,
             matrix: Some(no_ops),
             .. Instruction::default()
         },
+        Instruction {
+            name: "sbcd",
+            desc: Some(SBCD_DESC),
+            category: Category::Bcd,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
         Instruction {
             name: "scc",
+            title: Some("Scc"),
             desc: Some(SCC_DESC),
+            category: Category::ProgramControl,
+            presets: &scc_presets,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
             cc_codes: Some(&cc_codes),
             .. Instruction::default()
         },
+        Instruction {
+            name: "stop",
+            desc: Some(STOP_DESC),
+            category: Category::SystemControl,
+            override_output_w: Some(stop_desc),
+            .. Instruction::default()
+        },
         Instruction {
             name: "sub",
             desc: Some(SUB_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "suba",
+            desc: Some(SUBA_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "subi",
+            desc: Some(SUBI_DESC),
+            category: Category::IntegerArithmetic,
+            matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "subq",
             desc: Some(SUBQ_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "subx",
             desc: Some(SUBX_DESC),
+            category: Category::IntegerArithmetic,
             matrix: Some(two_ops),
+            cpu_matrices: two_ops_020,
             .. Instruction::default()
         },
         Instruction {
             name: "swap",
             desc: Some(SWAP_DESC),
+            category: Category::ShiftRotate,
             matrix: Some(one_op),
             .. Instruction::default()
         },
+        Instruction {
+            name: "tas",
+            desc: Some(TAS_DESC),
+            category: Category::Multiprocessor,
+            matrix: Some(one_op),
+            cpu_matrices: one_op_020,
+            .. Instruction::default()
+        },
         Instruction {
             name: "trap",
             desc: Some(TRAP_DESC),
-            override_output_w: Some(&trap_desc),
+            category: Category::SystemControl,
+            matrix: Some(vector_op),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "trapf",
+            title: Some("TRAPcc"),
+            desc: Some(TRAPCC_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68020,
+            matrix: Some(no_ops),
+            cc_codes: Some(cc_codes),
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "trapf",
+            title: Some("TRAPcc #imm"),
+            desc: Some(TRAPCC_IMM_DESC),
+            category: Category::SystemControl,
+            min_cpu: Cpu::M68020,
+            matrix: Some(imm_op),
+            cc_codes: Some(cc_codes),
             .. Instruction::default()
         },
         Instruction {
             name: "trapv",
             desc: Some(TRAPV_DESC),
-            override_output_w: Some(&trapv_desc),
-            matrix: Some(one_op),
+            category: Category::SystemControl,
+            matrix: Some(no_ops),
             .. Instruction::default()
         },
         Instruction {
             name: "tst",
             desc: Some(TST_DESC),
+            category: Category::ProgramControl,
             matrix: Some(one_op),
+            cpu_matrices: one_op_020,
+            .. Instruction::default()
+        },
+        Instruction {
+            name: "unlk",
+            desc: Some(UNLK_DESC),
+            category: Category::DataMovement,
+            matrix: Some(an_op),
             .. Instruction::default()
         },
     ];
@@ -1062,20 +2636,115 @@ fn main() {
        ];
        */
 
-    for inst in inst_2_ops_000.iter() {
-        let name_long = format!("{}.l", inst.name);
+    let cache = options.cache.as_ref().map(|dir| {
+        let mode = if options.verify_cache { cache::Mode::Verify } else { cache::Mode::Use };
 
-        print_instruction_header(inst);
-        generate_table(inst.name, &inst);
+        cache::Cache::open(dir, mode, options.clear_cache).unwrap_or_else(|err| {
+            eprintln!("error: unable to open the cache in {}: {}", dir, err);
+            process::exit(1);
+        })
+    });
+
+    let mut context = Context {
+        assembler: assembler::Selection::new(&options.assembler, options.cross_check),
+        cache,
+        diagnostics: diagnostics::Diagnostics::new(),
+    };
+
+    // Flat RAM of the emulated machine, the wrapper lays out vectors and code in it for every run
+    let mut ram = vec![0u8; options.ram_size];
+
+    unsafe {
+        m68k_wrapper_set_memory(ram.as_mut_ptr(), ram.len() as u32);
+    }
 
-        if !inst.has_override() {
-            generate_table(&name_long, &inst);
+    if let Some(ref lookup) = options.lookup {
+        for &cpu in &options.cpus {
+            unsafe {
+                m68k_wrapper_init(cpu.musashi_type());
+            }
+
+            if let Some(ref mut cache) = context.cache {
+                cache.set_emulator(cpu, options.ram_size);
+            }
+
+            let (point, result) = operands::lookup(&mut context, cpu, &lookup.mnemonic, lookup.src, lookup.dst);
+            let cycles = match (result.cycle_count, result.fault) {
+                (Some(cycles), _) => format!("{} cycles", cycles),
+                (None, Some(fault)) => format!("emulation fault, {}", fault.describe()),
+                (None, None) => match result.rejection {
+                    Some(ref rejection) => format!("rejected by the assembler, {}: {}", rejection.category.name(), rejection.message),
+                    None => "rejected by the assembler".to_owned(),
+                },
+            };
+
+            println!("MC{} {}, {} (n = {}): {}", cpu.name(), result.statement, operands::describe(&point), point.n, cycles);
+        }
+
+        save_cache(&mut context);
+        report_diagnostics(&context, &options);
+        return;
+    }
+
+    let cell_format = CellFormat { bus_cycles: options.bus_cycles, rejected_symbols: options.rejected_symbols };
+
+    // One documentation set per selected CPU
+    let mut sets = Vec::new();
+
+    for &cpu in &options.cpus {
+        unsafe {
+            m68k_wrapper_init(cpu.musashi_type());
+        }
+
+        if let Some(ref mut cache) = context.cache {
+            cache.set_emulator(cpu, options.ram_size);
+        }
+
+        if options.format == OutputFormat::Markdown && !options.compare {
+            print_docs(&generate_docs(&mut context, cpu, &inst_2_ops_000), cell_format, options.access_trace);
+        } else {
+            sets.push(generate_docs(&mut context, cpu, &inst_2_ops_000));
+        }
+    }
+
+    match options.format {
+        OutputFormat::Markdown => {
+            if options.compare {
+                compare::print_comparison(&inst_2_ops_000, &sets, cell_format);
+            }
+        }
+
+        OutputFormat::Json => {
+            let res = match options.output {
+                Some(ref path) => File::create(path).and_then(|mut file| json::write_documentation(&mut file, &sets)),
+                None => json::write_documentation(&mut std::io::stdout(), &sets),
+            };
+
+            if let Err(err) = res {
+                eprintln!("error: unable to write json output: {}", err);
+                process::exit(1);
+            }
         }
 
-        //generate_table(inst.name, false, Some(&src_types), &dest_types);
-        //generate_table(&name_long, true, Some(&src_types), &dest_types);
+        OutputFormat::Html => {
+            let dir = options.output.clone().unwrap_or("html".to_owned());
+
+            if let Err(err) = html::write_sites(Path::new(&dir), &sets, cell_format, options.access_trace) {
+                eprintln!("error: unable to write html site to {}: {}", dir, err);
+                process::exit(1);
+            }
+        }
+    }
+
+    report_unused_descriptions(&inst_2_ops_000);
+
+    if let Some(summary) = context.assembler.cross_check_summary() {
+        eprintln!("{}", summary);
     }
 
+    save_cache(&mut context);
+    report_diagnostics(&context, &options);
+
     /*
     {
         let inst = Instruction {
@@ -1118,7 +2787,103 @@ fn main() {
 }
 
 extern "C" {
-    fn m68k_wrapper_init();
-    fn m68k_run_instructions(instructions: *const raw::c_void, count: u32, cycle_res: *mut u32);
+    fn m68k_wrapper_init(cpu_type: u32);
+    fn m68k_wrapper_set_memory(memory: *mut u8, size: u32);
+    fn m68k_run_instructions(instructions: *const raw::c_void,
+                             size: u32,
+                             count: u32,
+                             offsets: *const u32,
+                             presets: *const Preset,
+                             cycle_res: *mut u32,
+                             bus: *mut BusCount,
+                             faults: *mut Fault,
+                             trace: *mut Access,
+                             trace_capacity: u32);
 }
 
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+    use std::sync::{Mutex, MutexGuard};
+
+    use super::*;
+
+    // Musashi and the wrapper are global, tests that run the emulator hold this lock
+    static EMULATOR: Mutex<()> = Mutex::new(());
+
+    // The emulator set up for a test with its own RAM. The wrapper's pointer to the RAM is
+    // cleared before the RAM goes away and the next test gets the emulator
+    struct Emulator {
+        _lock: MutexGuard<'static, ()>,
+        _ram: Vec<u8>,
+    }
+
+    impl Emulator {
+        fn new(cpu: Cpu) -> Emulator {
+            // A test that panicked poisons the lock, the emulator is set up from scratch anyway
+            let lock = EMULATOR.lock().unwrap_or_else(|err| err.into_inner());
+            let mut ram = vec![0u8; 1024 * 1024];
+
+            unsafe {
+                m68k_wrapper_set_memory(ram.as_mut_ptr(), ram.len() as u32);
+                m68k_wrapper_init(cpu.musashi_type());
+            }
+
+            Emulator { _lock: lock, _ram: ram }
+        }
+    }
+
+    impl Drop for Emulator {
+        fn drop(&mut self) {
+            unsafe {
+                m68k_wrapper_set_memory(ptr::null_mut(), 0);
+            }
+        }
+    }
+
+    // Runs a whole instruction table with the fake backend, which assembles every statement
+    // to a NOP, so every cell takes the 4 cycles of a NOP on the 68000
+    #[test]
+    fn fake_backend_table() {
+        let config = assembler::Config { backend: assembler::Backend::Fake, path: None, flags: Vec::new() };
+
+        let mut context = Context {
+            assembler: assembler::Selection::new(&config, false),
+            cache: None,
+            diagnostics: diagnostics::Diagnostics::new(),
+        };
+
+        let _emulator = Emulator::new(Cpu::M68000);
+
+        let src = [Op::new("d0", "Dn"), Op::new("#1", "#xxx")];
+        let dst = [Op::new("d1", "Dn"), Op::new("(a0)", "(An)"), Op::new("$800.W", "xxx.W")];
+        let matrix: &[&[Op]] = &[&src, &dst];
+
+        let inst = Instruction {
+            name: "add",
+            matrix: Some(matrix),
+            .. Instruction::default()
+        };
+
+        let tables = generate_tables(&mut context, Cpu::M68000, &inst);
+        assert_eq!(tables.len(), 1);
+
+        let table = match tables[0] {
+            Table::Measured(ref table) => table,
+            Table::Predefined(..) => panic!("add has no predefined table"),
+        };
+
+        assert_eq!(table.name, "add");
+        assert_eq!(table.statements.len(), 6);
+        assert_eq!(table.statements[3].statement, "add #1,d1");
+
+        for statement in &table.statements {
+            assert_eq!(statement.code, vec![0x4e, 0x71]);
+            assert_eq!(statement.cycle_count, Some(4));
+            assert!(statement.fault.is_none() && statement.rejection.is_none());
+        }
+
+        assert!(context.diagnostics.summary().is_none());
+        assert!(context.assembler.cross_check_summary().is_none());
+    }
+}
@@ -0,0 +1,267 @@
+// Timings of MULU/MULS/DIVU/DIVS measured over many register operand values. Only the
+// 68000 emulation models the data dependency, the other CPUs keep Musashi's fixed counts.
+//
+// MUL sweeps every 16 bit source. DIV sweeps a fixed set of pseudo random operands that
+// don't overflow, positive ones only for DIVS as the signs add their own cycles. The grid
+// tables of these instructions are measured with a single set of values, see grid_caption().
+
+use cpu::Cpu;
+use descriptions::Size;
+use sweep::Fit;
+use {assemble_statements, compile_cycle_counts};
+use {BuildResult, Context, Op, Preset, TableLayout, TimingTable};
+
+pub const MNEMONICS: &[&str] = &["mulu", "muls", "divu", "divs"];
+
+const DIV_POINTS: usize = 4096;
+
+// Register operands of one measurement, src is loaded into d1 and dst into d0. n is the
+// term of the formula fitted to the sweep
+#[derive(Copy, Clone)]
+pub struct Point {
+    pub src: u32,
+    pub dst: u32,
+    pub n: u32,
+}
+
+pub struct Summary {
+    pub min: usize,
+    pub max: usize,
+    pub average: f64,
+}
+
+// Cycles measured for every n
+pub struct Group {
+    pub n: u32,
+    pub values: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+// Deterministic pseudo random numbers so every run measures the same operands
+struct Random(u32);
+
+impl Random {
+    fn next(&mut self) -> u32 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        self.0 >> 16
+    }
+}
+
+// What n counts in the formula of an instruction
+pub fn n_meaning(mnemonic: &str) -> &'static str {
+    match mnemonic {
+        "mulu" => "ones in the source",
+        "muls" => "01/10 pairs in the source with a zero appended",
+        "divu" => "2 for every step of the 15 step shift and subtract loop that neither carries nor \
+                   subtracts and 1 for every one that subtracts without a carry",
+        _ => "zeros in the top 15 bits of the quotient",
+    }
+}
+
+// Steps of the 68000's unsigned shift and subtract loop (after Jorge Cwik) that don't shift
+// a carry out of the dividend: 2 if the divisor doesn't fit either, 1 if it's subtracted
+fn divu_steps(dividend: u32, divisor: u32) -> u32 {
+    let divisor = (divisor & 0xffff) << 16;
+    let mut dividend = dividend;
+    let mut n = 0;
+
+    for _ in 0..15 {
+        let carry = dividend & 0x80000000 != 0;
+        dividend <<= 1;
+
+        if carry {
+            dividend = dividend.wrapping_sub(divisor);
+        } else if dividend >= divisor {
+            dividend -= divisor;
+            n += 1;
+        } else {
+            n += 2;
+        }
+    }
+
+    n
+}
+
+fn count_n(mnemonic: &str, src: u32, dst: u32) -> u32 {
+    match mnemonic {
+        "mulu" => (src & 0xffff).count_ones(),
+        "muls" => {
+            let bits = (src & 0xffff) << 1;
+            ((bits ^ (bits >> 1)) & 0xffff).count_ones()
+        }
+        "divu" => divu_steps(dst, src),
+        _ => {
+            let dividend = (dst as i32).wrapping_abs() as u32;
+            let divisor = (src as u16 as i16 as i32).unsigned_abs();
+
+            match divisor {
+                0 => 0,
+                _ => 15 - ((dividend / divisor) & 0xfffe).count_ones(),
+            }
+        }
+    }
+}
+
+fn point(mnemonic: &str, src: u32, dst: u32) -> Point {
+    Point {
+        src,
+        dst,
+        n: count_n(mnemonic, src, dst),
+    }
+}
+
+fn points(mnemonic: &str) -> Vec<Point> {
+    let mut random = Random(1);
+
+    match mnemonic {
+        "mulu" | "muls" => (0..0x10000).map(|src| point(mnemonic, src, 1)).collect(),
+        _ => {
+            let mask = if mnemonic == "divs" { 0x7fff } else { 0xffff };
+
+            (0..DIV_POINTS).map(|_| {
+                let quotient = random.next() & mask;
+                let divisor = (random.next() & mask).max(1);
+                let remainder = random.next() % divisor;
+
+                point(mnemonic, divisor, quotient * divisor + remainder)
+            }).collect()
+        }
+    }
+}
+
+// Register and memory values the grid tables of an instruction with data dependent timing
+// are measured with
+pub(crate) fn grid_caption(preset: &Preset) -> String {
+    format!("Measured with Dn = ${:08x}, #xxx = 8 and memory operands reading $4e71 (the NOP landing area). \
+             The cycles of other values are in the operand sweep.", preset.d[0])
+}
+
+fn statement(mnemonic: &str) -> String {
+    format!("{}.w d1,d0", mnemonic)
+}
+
+fn build_result(statement: &str, point: &Point) -> BuildResult {
+    let mut preset = Preset::default();
+    preset.d[0] = point.dst;
+    preset.d[1] = point.src;
+
+    BuildResult {
+        src: None,
+        dst: Op::new("", ""),
+        statement: statement.to_owned(),
+        code: Vec::new(),
+        preset: Some(preset),
+        cycle_count: None,
+        bus: None,
+        fault: None,
+        trace: Vec::new(),
+        cases: None,
+        rejection: None,
+    }
+}
+
+// Runs the statement over every point. They all share the same code so it's only assembled once
+fn measure(context: &mut Context, cpu: Cpu, name: &str, mnemonic: &str, points: &[Point]) -> Vec<BuildResult> {
+    let statement = statement(mnemonic);
+    let mut statements: Vec<BuildResult> = points.iter().map(|point| build_result(&statement, point)).collect();
+
+    let mut first = vec![build_result(&statement, &points[0])];
+    assemble_statements(context, cpu, &mut first);
+
+    for result in &mut statements {
+        result.code = first[0].code.clone();
+        result.cycle_count = first[0].cycle_count;
+        result.rejection = first[0].rejection.clone();
+    }
+
+    compile_cycle_counts(context, name, &mut statements);
+    statements
+}
+
+pub(crate) fn generate_table<'a>(context: &mut Context, cpu: Cpu, mnemonic: &str) -> TimingTable<'a> {
+    let name = format!("{}.w Dn,Dn", mnemonic);
+    let points = points(mnemonic);
+    let statements = measure(context, cpu, &name, mnemonic, &points);
+
+    TimingTable {
+        name,
+        size: Some(Size::Word),
+        layout: TableLayout::Operands(points, n_meaning(mnemonic)),
+        statements,
+        caption: None,
+    }
+}
+
+// Measures a single operand combination
+pub(crate) fn lookup(context: &mut Context, cpu: Cpu, mnemonic: &str, src: u32, dst: u32) -> (Point, BuildResult) {
+    let point = point(mnemonic, src, dst);
+    let mut statements = measure(context, cpu, mnemonic, mnemonic, &[point]);
+
+    (point, statements.remove(0))
+}
+
+// Points of an operand sweep that were measured with their cycles
+pub(crate) fn measured(table: &TimingTable) -> Vec<(Point, usize)> {
+    match table.layout {
+        TableLayout::Operands(ref points, _) => {
+            points.iter()
+                .zip(&table.statements)
+                .filter_map(|(&point, result)| result.cycle_count.map(|cycles| (point, cycles)))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+pub fn summary(measured: &[(Point, usize)]) -> Option<Summary> {
+    if measured.is_empty() {
+        return None;
+    }
+
+    let total: usize = measured.iter().map(|m| m.1).sum();
+
+    Some(Summary {
+        min: measured.iter().map(|m| m.1).min().unwrap(),
+        max: measured.iter().map(|m| m.1).max().unwrap(),
+        average: total as f64 / measured.len() as f64,
+    })
+}
+
+pub fn groups(measured: &[(Point, usize)]) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+
+    for &(point, cycles) in measured {
+        match groups.iter().position(|g| g.n == point.n) {
+            Some(i) => {
+                let group = &mut groups[i];
+                group.values += 1;
+                group.min = group.min.min(cycles);
+                group.max = group.max.max(cycles);
+            }
+            None => groups.push(Group { n: point.n, values: 1, min: cycles, max: cycles }),
+        }
+    }
+
+    groups.sort_by_key(|g| g.n);
+    groups
+}
+
+// Measured points the formula doesn't give
+pub fn mismatches(fit: &Fit, measured: &[(Point, usize)]) -> Vec<(Point, usize)> {
+    measured.iter().filter(|&&(point, cycles)| fit.cycles(point.n) != cycles as i64).cloned().collect()
+}
+
+// Cycles of a group, e.g. 76 or 76-80
+pub fn group_cycles(group: &Group) -> String {
+    if group.min == group.max {
+        group.min.to_string()
+    } else {
+        format!("{}-{}", group.min, group.max)
+    }
+}
+
+// Register values of a point, e.g. d1 = $0007, d0 = $000186a0
+pub fn describe(point: &Point) -> String {
+    format!("d1 = ${:04x}, d0 = ${:08x}", point.src & 0xffff, point.dst)
+}
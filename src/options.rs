@@ -0,0 +1,318 @@
+use assembler::{self, Backend};
+use cpu::Cpu;
+use operands;
+
+pub const USAGE: &str = "Usage: 68k_documentation_table_gen [options]
+
+Options:
+    --format <markdown|json|html>  Output format (default: markdown)
+    --cpu <list>                   Comma separated CPUs to document: 68000, 68010, 68ec020,
+                                   68020 or all (default: 68000)
+    --ram <size>                   Size of the emulated flat RAM, e.g. 512K or 16M
+                                   (64K - 16M, default: 1M)
+    --bus-cycles                   Show cells as total(reads/writes) bus cycles, e.g. 12(2/1)
+    --access-trace                 List the bus accesses of every measured instruction in
+                                   order (markdown and html, json always includes them)
+    --lookup <inst>=<value>        Measure MULU/MULS with a source value or DIVU/DIVS with a
+                                   dividend/divisor pair on every selected CPU, e.g.
+                                   mulu=$ff00 or divs=-100000/7
+    --compare                      Print the timings of all selected CPUs side by side
+                                   (markdown only, default cpus: 68000,68010,68020)
+    --assembler <backend>          Assembler backend: vasm, gas (GNU as, Motorola syntax),
+                                   gas-mit (GNU as, MIT syntax), builtin (68000 encoder,
+                                   requires --cpu 68000) or fake (NOPs only) (default: vasm)
+    --assembler-path <path>        Executable of the assembler (default: vasmm68k_mot or
+                                   m68k-elf-as, objcopy is taken from the same toolchain)
+    --assembler-flags <flags>      Space separated flags passed on to the assembler
+    --cross-check                  Assemble with the built-in encoder as well (vasm if that
+                                   is the backend, with the path and flags above) and report
+                                   statements whose machine code differs (68000 only)
+    --diagnostics <path>           Write a report of every statement the assembler rejected,
+                                   sorted into illegal EA, unsupported size, syntax error
+                                   and tool failure, to a markdown file
+    --rejected-symbols <symbols>   Cell symbols of the four categories in that order
+                                   (default: ****, e.g. *s?! to tell them apart)
+    --cache <dir>                  Keep assembled statements and measurements in dir and
+                                   reuse them in later runs
+    --clear-cache                  Remove the cached entries before the run
+    --verify-cache                 Assemble and measure everything and compare the results
+                                   with the cached entries, exits with an error on mismatches
+    --output <path>                File to write json output to (default: stdout) or
+                                   directory to write the html site to (default: html)";
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    pub output: Option<String>,
+    pub cpus: Vec<Cpu>,
+    pub compare: bool,
+    pub bus_cycles: bool,
+    pub access_trace: bool,
+    pub ram_size: usize,
+    pub lookup: Option<Lookup>,
+    pub assembler: assembler::Config,
+    pub cross_check: bool,
+    pub diagnostics: Option<String>,
+    // Symbol of a rejected cell per category, see assembler::Category
+    pub rejected_symbols: [char; 4],
+    // Directory of the assembly and measurement cache
+    pub cache: Option<String>,
+    pub clear_cache: bool,
+    pub verify_cache: bool,
+}
+
+// Operands to measure a single MUL/DIV with, src is the source (divisor) and dst the destination (dividend)
+pub struct Lookup {
+    pub mnemonic: String,
+    pub src: u32,
+    pub dst: u32,
+}
+
+pub const MIN_RAM_SIZE: usize = 64 * 1024;
+pub const MAX_RAM_SIZE: usize = 16 * 1024 * 1024;
+
+// Plain byte count or a K/M suffixed size
+fn parse_size(text: &str) -> Result<usize, String> {
+    let upper = text.to_uppercase();
+
+    let (digits, scale) = if upper.ends_with('K') {
+        (&upper[..upper.len() - 1], 1024)
+    } else if upper.ends_with('M') {
+        (&upper[..upper.len() - 1], 1024 * 1024)
+    } else {
+        (&upper[..], 1)
+    };
+
+    let size = match digits.parse::<usize>().ok().and_then(|value| value.checked_mul(scale)) {
+        Some(size) => size,
+        None => return Err(format!("invalid ram size '{}'", text)),
+    };
+
+    if !(MIN_RAM_SIZE..=MAX_RAM_SIZE).contains(&size) {
+        return Err(format!("ram size '{}' is outside of 64K - 16M", text));
+    }
+
+    Ok(size)
+}
+
+// Decimal (optionally negative) or $/0x prefixed hex value
+fn parse_value(text: &str) -> Result<u32, String> {
+    let value = if let Some(hex) = text.strip_prefix('$') {
+        u32::from_str_radix(hex, 16).ok()
+    } else if let Some(hex) = text.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).ok()
+    } else if text.starts_with('-') {
+        text.parse::<i32>().ok().map(|v| v as u32)
+    } else {
+        text.parse::<u32>().ok()
+    };
+
+    value.ok_or(format!("invalid value '{}'", text))
+}
+
+fn parse_lookup(text: &str) -> Result<Lookup, String> {
+    let mut parts = text.splitn(2, '=');
+    let mnemonic = parts.next().unwrap().to_lowercase();
+    let operands = parts.next().ok_or(format!("--lookup expects <inst>=<value>, got '{}'", text))?;
+
+    if !operands::MNEMONICS.contains(&mnemonic.as_str()) {
+        return Err(format!("--lookup supports {}, not '{}'", operands::MNEMONICS.join(", "), mnemonic));
+    }
+
+    if mnemonic.starts_with("mul") {
+        return Ok(Lookup { mnemonic, src: parse_value(operands)?, dst: 1 });
+    }
+
+    let mut values = operands.splitn(2, '/');
+    let dividend = parse_value(values.next().unwrap())?;
+    let divisor = parse_value(values.next().ok_or(format!("{} expects <dividend>/<divisor>", mnemonic))?)?;
+
+    if divisor & 0xffff == 0 {
+        return Err("the divisor can't be zero".to_owned());
+    }
+
+    Ok(Lookup { mnemonic, src: divisor, dst: dividend })
+}
+
+// One character per rejection category, e.g. *s?!
+fn parse_symbols(text: &str) -> Result<[char; 4], String> {
+    let chars: Vec<char> = text.chars().collect();
+
+    match chars.len() {
+        4 => Ok([chars[0], chars[1], chars[2], chars[3]]),
+        _ => Err(format!("--rejected-symbols expects one symbol for each of the four categories, got '{}'", text)),
+    }
+}
+
+fn parse_cpus(list: &str) -> Result<Vec<Cpu>, String> {
+    if list == "all" {
+        return Ok(Cpu::all().to_vec());
+    }
+
+    let mut cpus = Vec::new();
+
+    for name in list.split(',') {
+        match Cpu::from_name(name.trim()) {
+            Some(cpu) => if !cpus.contains(&cpu) { cpus.push(cpu) },
+            None => return Err(format!("unknown cpu '{}'", name)),
+        }
+    }
+
+    Ok(cpus)
+}
+
+impl Options {
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options {
+            format: OutputFormat::Markdown,
+            output: None,
+            cpus: Vec::new(),
+            compare: false,
+            bus_cycles: false,
+            access_trace: false,
+            ram_size: 1024 * 1024,
+            lookup: None,
+            assembler: assembler::Config { backend: Backend::Vasm, path: None, flags: Vec::new() },
+            cross_check: false,
+            diagnostics: None,
+            rejected_symbols: ['*'; 4],
+            cache: None,
+            clear_cache: false,
+            verify_cache: false,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    options.format = match args.next() {
+                        Some(ref format) if format == "markdown" => OutputFormat::Markdown,
+                        Some(ref format) if format == "json" => OutputFormat::Json,
+                        Some(ref format) if format == "html" => OutputFormat::Html,
+                        Some(format) => return Err(format!("unknown format '{}'", format)),
+                        None => return Err("--format expects a value".to_owned()),
+                    };
+                }
+
+                "--output" => {
+                    match args.next() {
+                        Some(path) => options.output = Some(path),
+                        None => return Err("--output expects a path".to_owned()),
+                    }
+                }
+
+                "--cpu" => {
+                    match args.next() {
+                        Some(list) => options.cpus = parse_cpus(&list)?,
+                        None => return Err("--cpu expects a list of cpus".to_owned()),
+                    }
+                }
+
+                "--ram" => {
+                    match args.next() {
+                        Some(size) => options.ram_size = parse_size(&size)?,
+                        None => return Err("--ram expects a size".to_owned()),
+                    }
+                }
+
+                "--lookup" => {
+                    match args.next() {
+                        Some(lookup) => options.lookup = Some(parse_lookup(&lookup)?),
+                        None => return Err("--lookup expects <inst>=<value>".to_owned()),
+                    }
+                }
+
+                "--assembler" => {
+                    match args.next() {
+                        Some(name) => match Backend::from_name(&name) {
+                            Some(backend) => options.assembler.backend = backend,
+                            None => return Err(format!("unknown assembler '{}'", name)),
+                        },
+                        None => return Err("--assembler expects vasm, gas, gas-mit, builtin or fake".to_owned()),
+                    }
+                }
+
+                "--assembler-path" => {
+                    match args.next() {
+                        Some(path) => options.assembler.path = Some(path),
+                        None => return Err("--assembler-path expects a path".to_owned()),
+                    }
+                }
+
+                "--assembler-flags" => {
+                    match args.next() {
+                        Some(flags) => options.assembler.flags = flags.split_whitespace().map(str::to_owned).collect(),
+                        None => return Err("--assembler-flags expects a list of flags".to_owned()),
+                    }
+                }
+
+                "--diagnostics" => {
+                    match args.next() {
+                        Some(path) => options.diagnostics = Some(path),
+                        None => return Err("--diagnostics expects a path".to_owned()),
+                    }
+                }
+
+                "--rejected-symbols" => {
+                    match args.next() {
+                        Some(symbols) => options.rejected_symbols = parse_symbols(&symbols)?,
+                        None => return Err("--rejected-symbols expects four symbols".to_owned()),
+                    }
+                }
+
+                "--cache" => {
+                    match args.next() {
+                        Some(dir) => options.cache = Some(dir),
+                        None => return Err("--cache expects a directory".to_owned()),
+                    }
+                }
+
+                "--compare" => options.compare = true,
+                "--cross-check" => options.cross_check = true,
+                "--bus-cycles" => options.bus_cycles = true,
+                "--access-trace" => options.access_trace = true,
+                "--clear-cache" => options.clear_cache = true,
+                "--verify-cache" => options.verify_cache = true,
+
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        if options.output.is_some() && options.format == OutputFormat::Markdown {
+            return Err("--output is only supported for json and html output".to_owned());
+        }
+
+        if options.compare && options.format != OutputFormat::Markdown {
+            return Err("--compare is only supported for markdown output".to_owned());
+        }
+
+        if options.compare && options.access_trace {
+            return Err("--access-trace can't be combined with --compare".to_owned());
+        }
+
+        if (options.clear_cache || options.verify_cache) && options.cache.is_none() {
+            return Err("--clear-cache and --verify-cache require --cache".to_owned());
+        }
+
+        if options.cpus.is_empty() {
+            options.cpus = if options.compare {
+                vec![Cpu::M68000, Cpu::M68010, Cpu::M68020]
+            } else {
+                vec![Cpu::M68000]
+            };
+        }
+
+        let builtin = options.assembler.backend == Backend::Builtin || options.cross_check;
+
+        if builtin && options.cpus.iter().any(|&cpu| cpu != Cpu::M68000) {
+            return Err("the built-in encoder only covers the 68000, select it with --cpu 68000".to_owned());
+        }
+
+        Ok(options)
+    }
+}
@@ -0,0 +1,55 @@
+// Fitting of base + step·n formulas to timings measured over a range of values
+// (shift counts, register counts, ...).
+
+#[derive(Copy, Clone, PartialEq)]
+pub struct Fit {
+    pub base: i64,
+    pub step: i64,
+}
+
+impl Fit {
+    pub fn cycles(&self, n: u32) -> i64 {
+        self.base + self.step * n as i64
+    }
+
+    pub fn formula(&self) -> String {
+        match self.step {
+            0 => self.base.to_string(),
+            1 => format!("{}+n", self.base),
+            -1 => format!("{}-n", self.base),
+            step if step < 0 => format!("{}-{}n", self.base, -step),
+            step => format!("{}+{}n", self.base, step),
+        }
+    }
+}
+
+// Least squares fit rounded to whole cycles. Returns None if there is nothing to fit.
+pub fn fit(points: &[(u32, usize)]) -> Option<Fit> {
+    if points.is_empty() {
+        return None;
+    }
+
+    let len = points.len() as f64;
+    let mean_n = points.iter().map(|p| p.0 as f64).sum::<f64>() / len;
+    let mean_cycles = points.iter().map(|p| p.1 as f64).sum::<f64>() / len;
+
+    let mut covariance = 0.0;
+    let mut variance = 0.0;
+
+    for &(n, cycles) in points {
+        covariance += (n as f64 - mean_n) * (cycles as f64 - mean_cycles);
+        variance += (n as f64 - mean_n) * (n as f64 - mean_n);
+    }
+
+    let step = if variance == 0.0 { 0.0 } else { (covariance / variance).round() };
+
+    Some(Fit {
+        base: (mean_cycles - step * mean_n).round() as i64,
+        step: step as i64,
+    })
+}
+
+// Points where the measurement and the formula disagree
+pub fn mismatches(fit: &Fit, points: &[(u32, usize)]) -> Vec<(u32, usize)> {
+    points.iter().filter(|&&(n, cycles)| fit.cycles(n) != cycles as i64).cloned().collect()
+}